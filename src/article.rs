@@ -4,16 +4,98 @@ use std::io::{Error, ErrorKind, Write};
 use std::path::PathBuf;
 use url::Url;
 
+/// Where a piece of metadata of an `Article` was found
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MetadataSource {
+    /// XPath rules of the site config
+    SiteConfig,
+    /// XPath rules of `global.txt`
+    GlobalConfig,
+    /// schema.org `<script type="application/ld+json">` block
+    JsonLd,
+    /// Generic `<meta>` tags of the page
+    Meta,
+    /// Byline detected by the readability algorithm
+    Byline,
+    /// Data embedded in the page, found by a `SiteExtractor`
+    SiteExtractor,
+}
+
+/// Author of an `Article`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+    /// Profile page of the author
+    pub url: Option<String>,
+}
+
+impl Author {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: None,
+        }
+    }
+}
+
+#[non_exhaustive]
 pub struct Article {
     pub title: Option<String>,
+    /// Names of all `authors` joined by `, `
     pub author: Option<String>,
+    /// Authors in the order they are credited
+    pub authors: Vec<Author>,
+    pub author_source: Option<MetadataSource>,
     pub url: Url,
+    /// Date the article was published
     pub date: Option<DateTime<Utc>>,
+    /// Date the article was last updated
+    pub date_modified: Option<DateTime<Utc>>,
     pub thumbnail_url: Option<String>,
+    /// Short description of the article or its first paragraph
+    pub excerpt: Option<String>,
+    pub site_name: Option<String>,
+    /// Language of the page, e.g. `en-US`
+    pub language: Option<String>,
+    /// Text direction, `ltr` or `rtl`
+    pub direction: Option<String>,
+    /// Number of characters of the text content of `html`
+    pub text_length: usize,
+    /// Name of the encoding the HTML was decoded from, e.g. `Shift_JIS`.
+    /// Only set by the entry points that take raw bytes.
+    pub encoding: Option<String>,
     pub html: Option<String>,
 }
 
 impl Article {
+    pub fn new(url: Url) -> Self {
+        Self {
+            title: None,
+            author: None,
+            authors: Vec::new(),
+            author_source: None,
+            url,
+            date: None,
+            date_modified: None,
+            thumbnail_url: None,
+            excerpt: None,
+            site_name: None,
+            language: None,
+            direction: None,
+            text_length: 0,
+            encoding: None,
+            html: None,
+        }
+    }
+
+    pub(crate) fn set_authors(&mut self, authors: Vec<Author>, source: MetadataSource) {
+        let names: Vec<&str> = authors.iter().map(|author| author.name.as_str()).collect();
+        self.author = Some(names.join(", "));
+        self.authors = authors;
+        self.author_source = Some(source);
+    }
+
     pub fn save_html(&self, path: &PathBuf) -> Result<(), Error> {
         if let Some(html) = self.html.as_deref() {
             if let Ok(()) = std::fs::create_dir_all(path) {
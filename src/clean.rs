@@ -1,6 +1,6 @@
 use crate::full_text_parser::error::FullTextParserError;
 use crate::util::Util;
-use crate::{FtrConfigEntry, FullTextParser};
+use crate::{ExtractionOptions, FtrConfigEntry, FullTextParser, PassContext};
 use url::Url;
 
 pub struct CleanedHtml {
@@ -36,18 +36,42 @@ pub fn clean_html_fragment(
     html_fragment: &str,
     base_url: &Url,
 ) -> Result<CleanedHtml, FullTextParserError> {
-    libxml::tree::node::set_node_rc_guard(10);
+    clean_html_fragment_with_options(html_fragment, base_url, &ExtractionOptions::default())
+}
 
+/// Like `clean_html_fragment`, but with `options` to control which cleanup steps are applied
+pub fn clean_html_fragment_with_options(
+    html_fragment: &str,
+    base_url: &Url,
+    options: &ExtractionOptions,
+) -> Result<CleanedHtml, FullTextParserError> {
     let empty_config = FtrConfigEntry::default();
     let document = FullTextParser::parse_html(html_fragment, None, &empty_config)?;
     let xpath_ctx = FullTextParser::get_xpath_ctx(&document)?;
     let thumbnail = FullTextParser::check_for_thumbnail(&xpath_ctx);
-    FullTextParser::prep_content(&xpath_ctx, None, &empty_config, base_url, &document, None);
-    if let Some(mut root) = document.get_root_element() {
-        FullTextParser::post_process_page(&mut root)?;
+    FullTextParser::prep_content(
+        &xpath_ctx,
+        None,
+        &empty_config,
+        base_url,
+        &document,
+        None,
+        options,
+    );
+    if let Some(root) = document.get_root_element() {
+        FullTextParser::post_process_page(&PassContext {
+            document: &document,
+            context: &xpath_ctx,
+            root,
+            config: None,
+            global_config: &empty_config,
+            url: base_url,
+            title: None,
+            options,
+        })?;
     }
     FullTextParser::prevent_self_closing_tags(&xpath_ctx)?;
-    FullTextParser::post_process_document(&document)?;
+    FullTextParser::post_process_document(&document, None, &empty_config, base_url, options)?;
 
     let content_node = if let Some(root) = document.get_root_element() {
         if root.get_name() == "body" {
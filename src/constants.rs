@@ -4,6 +4,9 @@ use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
 
 pub const DEFAULT_CHAR_THRESHOLD: usize = 500;
+pub const MAX_PAGES: usize = 10;
+pub const READERABLE_MIN_CONTENT_LENGTH: usize = 140;
+pub const READERABLE_MIN_SCORE: f64 = 20.0;
 pub static IS_IMAGE: Lazy<Regex> = Lazy::new(|| {
     RegexBuilder::new(r#"\.(jpg|jpeg|png|webp)"#)
         .case_insensitive(true)
@@ -36,6 +39,21 @@ pub static BYLINE: Lazy<Regex> = Lazy::new(|| {
         .build()
         .expect("BYLINE regex")
 });
+/// schema.org types of JSON-LD objects describing an article
+pub static JSON_LD_ARTICLE_TYPES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(Article|AdvertiserContentArticle|NewsArticle|AnalysisNewsArticle|AskPublicNewsArticle|BackgroundNewsArticle|OpinionNewsArticle|ReportageNewsArticle|ReviewNewsArticle|Report|SatiricalArticle|ScholarlyArticle|MedicalScholarlyArticle|SocialMediaPosting|BlogPosting|LiveBlogPosting|DiscussionForumPosting|TechArticle|APIReference)$"#)
+        .expect("JSON_LD_ARTICLE_TYPES regex")
+});
+/// Times, numbers and words of a date string like `Oct 17th 2026, 8:30 pm`
+pub static DATE_TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\d{1,2}:\d{2}(?::\d{2})?|\d+|[^\W\d_]+"#).expect("DATE_TOKEN regex")
+});
+pub static META_CHARSET: Lazy<Regex> = Lazy::new(|| {
+    RegexBuilder::new(r#"<meta[^>]*?charset\s*=\s*["']?\s*([a-z0-9_:.+-]+)"#)
+        .case_insensitive(true)
+        .build()
+        .expect("META_CHARSET regex")
+});
 pub static NORMALIZE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\s{2,}"#).expect("NORMALIZE regex"));
 pub static TOKENIZE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\W+"#).expect("TOKENIZE regex"));
 pub static UNLIELY_CANDIDATES: Lazy<Regex> = Lazy::new(|| {
@@ -83,8 +101,9 @@ pub static TITLE_CUT_FRONT: Lazy<Regex> = Lazy::new(|| {
         .build()
         .expect("TITLE_CUT_FRONT regex")
 });
+/// Default allowlist of video embeds, `/videos/embed/` matches PeerTube instances
 pub static VIDEOS: Lazy<Regex> = Lazy::new(|| {
-    RegexBuilder::new(r#"(www\.)?((dailymotion|youtube|youtube-nocookie|player\.vimeo|v\.qq)\.com|(archive|upload\.wikimedia)\.org|player\.twitch\.tv)"#).case_insensitive(true).build().expect("VIDEOS regex")
+    RegexBuilder::new(r#"(www\.)?((dailymotion|youtube|youtube-nocookie|player\.vimeo|v\.qq)\.com|(archive|upload\.wikimedia)\.org|player\.twitch\.tv|/videos/embed/)"#).case_insensitive(true).build().expect("VIDEOS regex")
 });
 pub static BASE64_DATA_URL: Lazy<Regex> = Lazy::new(|| {
     RegexBuilder::new(r#"^data:\s*([^\s;,]+)\s*;\s*base64\s*,"#)
@@ -94,6 +113,7 @@ pub static BASE64_DATA_URL: Lazy<Regex> = Lazy::new(|| {
 });
 pub const SCORE_ATTR: &str = "content_score";
 pub const DATA_TABLE_ATTR: &str = "is_data_table";
+pub const ALLOWED_VIDEO_ATTR: &str = "is_allowed_video";
 pub const MINIMUM_TOPCANDIDATES: usize = 3;
 pub const UNLIKELY_ROLES: &[&str] = &[
     "menu",
@@ -121,6 +141,27 @@ pub const PRESENTATIONAL_ATTRIBUTES: &[&str] = &[
     "valign",
     "vspace",
 ];
+/// Elements and their attributes that hold a single URL
+pub const URL_ATTRIBUTES: &[(&str, &str)] = &[
+    ("a", "href"),
+    ("a", "src"),
+    ("area", "href"),
+    ("img", "src"),
+    ("source", "src"),
+    ("video", "src"),
+    ("video", "poster"),
+    ("audio", "src"),
+    ("track", "src"),
+    ("object", "data"),
+    ("embed", "src"),
+    ("iframe", "src"),
+    ("blockquote", "cite"),
+    ("q", "cite"),
+    ("ins", "cite"),
+    ("del", "cite"),
+];
+/// Elements with a `srcset` list of image candidates
+pub const SRCSET_ELEMS: &[&str] = &["img", "source"];
 pub static DEPRECATED_SIZE_ATTRIBUTE_ELEMS: Lazy<HashSet<&str>> =
     Lazy::new(|| HashSet::from(["TABLE", "TH", "TD", "HR", "PRE"]));
 pub static DIV_TO_P_ELEMS: Lazy<HashSet<&str>> = Lazy::new(|| {
@@ -1,7 +1,7 @@
 use rust_embed::RustEmbed;
 use std::{borrow::Borrow, collections::HashMap, fs, path::Path};
 
-use super::ConfigEntry;
+use super::{ConfigDiagnostic, ConfigEntry};
 use crate::util::Util;
 
 #[derive(RustEmbed)]
@@ -11,12 +11,14 @@ struct EmbededConfigFiles;
 pub struct ConfigCollection {
     embedded_entries: HashMap<String, ConfigEntry>,
     user_entries: HashMap<String, ConfigEntry>,
+    user_diagnostics: Vec<ConfigDiagnostic>,
 }
 
 impl ConfigCollection {
     pub fn parse(directory: Option<&Path>) -> ConfigCollection {
         let mut user_entries = HashMap::new();
         let mut embedded_entries = HashMap::new();
+        let mut user_diagnostics = Vec::new();
 
         for (file_name, entry) in EmbededConfigFiles::iter()
             .filter_map(|file_name| EmbededConfigFiles::get(&file_name).map(|e| (file_name, e)))
@@ -45,7 +47,12 @@ impl ConfigCollection {
             if let Ok(mut dir) = fs::read_dir(directory) {
                 while let Some(Ok(entry)) = dir.next() {
                     if Util::check_extension(&entry, "txt") {
-                        if let Ok(config) = ConfigEntry::parse_path(&entry.path()) {
+                        if let Ok((config, diagnostics)) = ConfigEntry::lint_path(&entry.path()) {
+                            for diagnostic in &diagnostics {
+                                log::warn!("{diagnostic}");
+                            }
+                            user_diagnostics.extend(diagnostics);
+
                             let file_name = entry.file_name().to_string_lossy().into_owned();
                             user_entries.insert(file_name, config);
                         }
@@ -57,9 +64,29 @@ impl ConfigCollection {
         Self {
             embedded_entries,
             user_entries,
+            user_diagnostics,
         }
     }
 
+    /// Problems found in the user configs while loading them
+    pub fn diagnostics(&self) -> &[ConfigDiagnostic] {
+        &self.user_diagnostics
+    }
+
+    /// Lint all configs embedded in the crate
+    pub fn lint_embedded() -> Vec<ConfigDiagnostic> {
+        EmbededConfigFiles::iter()
+            .filter(|file_name| file_name.ends_with(".txt"))
+            .filter_map(|file_name| {
+                EmbededConfigFiles::get(&file_name).map(|entry| {
+                    let (_config, diagnostics) = ConfigEntry::lint_data(&entry.data, &file_name);
+                    diagnostics
+                })
+            })
+            .flatten()
+            .collect()
+    }
+
     pub fn get(&self, key: &str) -> Option<&ConfigEntry> {
         if let Some(user_entry) = self.user_entries.get(key) {
             Some(user_entry)
@@ -67,6 +94,19 @@ impl ConfigCollection {
             self.embedded_entries.get(key)
         }
     }
+
+    /// All config entries by file name. User entries replace embedded ones of the same name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ConfigEntry)> {
+        let embedded = self
+            .embedded_entries
+            .iter()
+            .filter(|(key, _)| !self.user_entries.contains_key(*key));
+
+        self.user_entries
+            .iter()
+            .chain(embedded)
+            .map(|(key, entry)| (key.as_str(), entry))
+    }
 }
 
 #[cfg(test)]
@@ -79,4 +119,13 @@ mod tests {
         let path = Path::new("~/.local/share/news-flash/ftr-site-config");
         let _collection = ConfigCollection::parse(Some(path));
     }
+
+    #[test]
+    fn lint_embedded() {
+        let diagnostics = ConfigCollection::lint_embedded();
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.directive == "titl"
+                && diagnostic.reason == "unknown directive"));
+    }
 }
@@ -1,23 +1,81 @@
 use crate::util::Util;
 
-use super::error::ConfigError;
+use super::error::{ConfigDiagnostic, ConfigError};
+use crate::full_text_parser::date::parse_time_zone;
 use std::borrow::Cow;
 use std::fs;
 use std::io::{BufRead, BufReader, Cursor, Read};
 use std::path::Path;
 
+/// Directives of Full-Text RSS that are valid but not supported (yet)
+const UNSUPPORTED_DIRECTIVES: &[&str] = &[
+    "login_uri:",
+    "login_username_field:",
+    "login_password_field:",
+    "login_extra_fields:",
+    "requires_login:",
+    "not_logged_in_xpath:",
+    "convert_double_br_tags:",
+    "insert_detected_image:",
+    "native_ad_clue:",
+    "parser:",
+    "footnotes:",
+    "strip_comments:",
+    "autodetect_next_page:",
+    "skip_id_or_class:",
+    "single_page_link_in_feed:",
+];
+
 #[derive(Clone, Debug)]
 pub struct Replace {
     pub to_replace: String,
     pub replace_with: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Header {
     pub name: String,
     pub value: String,
 }
 
+/// A `single_page_link` or `next_page_link` rule
+///
+/// The rule only applies if the page matches the xpath of the
+/// `if_page_contains` directive directly following it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageLink {
+    pub xpath: String,
+    pub condition: Option<String>,
+}
+
+impl PageLink {
+    pub fn new(xpath: &str) -> Self {
+        Self {
+            xpath: xpath.into(),
+            condition: None,
+        }
+    }
+}
+
+/// A `test_url` of the site config and the `test_contains` strings following it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestUrl {
+    pub url: String,
+    pub contains: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct WrapIn {
+    pub tag: String,
+    pub xpath: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct MoveInto {
+    pub target: String,
+    pub xpath: String,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ConfigEntry {
     pub xpath_title: Vec<String>,
@@ -27,18 +85,44 @@ pub struct ConfigEntry {
     pub xpath_strip: Vec<String>,
     pub strip_id_or_class: Vec<String>,
     pub strip_image_src: Vec<String>,
+    pub strip_attr: Vec<String>,
+    pub src_lazy_load_attr: Option<String>,
     pub replace: Vec<Replace>,
     pub header: Vec<Header>,
-    pub single_page_link: Option<String>,
-    pub next_page_link: Option<String>,
+    pub single_page_link: Vec<PageLink>,
+    pub next_page_link: Vec<PageLink>,
+    pub wrap_in: Vec<WrapIn>,
+    pub dissolve: Vec<String>,
+    pub move_into: Vec<MoveInto>,
+    pub test_url: Vec<TestUrl>,
+    /// Use `global.txt` and readability if the site rules fail, defaults to yes
+    pub autodetect_on_failure: Option<bool>,
+    /// Remove elements that don't look like content from the body, defaults to yes
+    pub prune: Option<bool>,
+    /// Don't read metadata from JSON-LD
+    pub skip_json_ld: bool,
+    /// Time zone of dates without offset, e.g. `Europe/Berlin` or `+01:00`. Defaults to
+    /// UTC. An extension of this crate, Full-Text RSS doesn't know this directive.
+    pub timezone: Option<String>,
 }
 
 impl ConfigEntry {
+    pub fn autodetect_on_failure(&self) -> bool {
+        self.autodetect_on_failure.unwrap_or(true)
+    }
+
+    pub fn prune(&self) -> bool {
+        self.prune.unwrap_or(true)
+    }
+
     pub fn parse_path(config_path: &Path) -> Result<ConfigEntry, ConfigError> {
         let mut file = fs::File::open(config_path)?;
         let buffer = BufReader::new(&mut file);
 
-        Self::parse(buffer)
+        let mut linter = Linter::new(None, false);
+        let config = Self::parse(buffer, &mut linter);
+        linter.log();
+        Ok(config)
     }
 
     pub fn parse_data(data: Cow<'static, [u8]>) -> Result<ConfigEntry, ConfigError> {
@@ -46,10 +130,59 @@ impl ConfigEntry {
         let mut cursor = Cursor::new(data);
         let buffer = BufReader::new(&mut cursor);
 
-        Self::parse(buffer)
+        let mut linter = Linter::new(None, false);
+        let config = Self::parse(buffer, &mut linter);
+        linter.log();
+        Ok(config)
+    }
+
+    /// Parse the config at `config_path` and fail with `ConfigError::Invalid` if any
+    /// line is unknown, malformed or contains an invalid xpath
+    pub fn parse_path_strict(config_path: &Path) -> Result<ConfigEntry, ConfigError> {
+        let (config, diagnostics) = Self::lint_path(config_path)?;
+        if diagnostics.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid(diagnostics))
+        }
+    }
+
+    /// Strict version of `parse_data`, see `parse_path_strict`
+    pub fn parse_data_strict(data: &[u8], file_name: &str) -> Result<ConfigEntry, ConfigError> {
+        let (config, diagnostics) = Self::lint_data(data, file_name);
+        if diagnostics.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid(diagnostics))
+        }
     }
 
-    fn parse<R: Read + Unpin>(buffer: BufReader<R>) -> Result<ConfigEntry, ConfigError> {
+    /// Parse the config at `config_path` and return it together with all problems found
+    pub fn lint_path(
+        config_path: &Path,
+    ) -> Result<(ConfigEntry, Vec<ConfigDiagnostic>), ConfigError> {
+        let mut file = fs::File::open(config_path)?;
+        let buffer = BufReader::new(&mut file);
+        let file_name = config_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+
+        let mut linter = Linter::new(file_name, true);
+        let config = Self::parse(buffer, &mut linter);
+        Ok((config, linter.diagnostics))
+    }
+
+    /// Parse `data` and return it together with all problems found
+    pub fn lint_data(data: &[u8], file_name: &str) -> (ConfigEntry, Vec<ConfigDiagnostic>) {
+        let mut cursor = Cursor::new(data);
+        let buffer = BufReader::new(&mut cursor);
+
+        let mut linter = Linter::new(Some(file_name.into()), true);
+        let config = Self::parse(buffer, &mut linter);
+        (config, linter.diagnostics)
+    }
+
+    fn parse<R: Read + Unpin>(buffer: BufReader<R>, linter: &mut Linter) -> ConfigEntry {
         let mut xpath_title: Vec<String> = Vec::new();
         let mut xpath_author: Vec<String> = Vec::new();
         let mut xpath_date: Vec<String> = Vec::new();
@@ -57,12 +190,25 @@ impl ConfigEntry {
         let mut xpath_strip: Vec<String> = Vec::new();
         let mut strip_id_or_class: Vec<String> = Vec::new();
         let mut strip_image_src: Vec<String> = Vec::new();
+        let mut strip_attr: Vec<String> = Vec::new();
+        let mut src_lazy_load_attr: Option<String> = None;
         let mut replace_vec: Vec<Replace> = Vec::new();
         let mut header_vec: Vec<Header> = Vec::new();
-        let mut next_page_link: Option<String> = None;
-        let mut single_page_link: Option<String> = None;
+        let mut next_page_link: Vec<PageLink> = Vec::new();
+        let mut single_page_link: Vec<PageLink> = Vec::new();
+        // whether an `if_page_contains` applies to the last `single_page_link` (true)
+        // or `next_page_link` (false) rule, reset by every other directive
+        let mut last_page_link_single: Option<bool> = None;
+        let mut wrap_in_vec: Vec<WrapIn> = Vec::new();
+        let mut dissolve_vec: Vec<String> = Vec::new();
+        let mut move_into_vec: Vec<MoveInto> = Vec::new();
+        let mut test_url_vec: Vec<TestUrl> = Vec::new();
+        let mut autodetect_on_failure: Option<bool> = None;
+        let mut prune: Option<bool> = None;
+        let mut skip_json_ld = false;
+        let mut timezone: Option<String> = None;
 
-        // ignore: tidy, prune, autodetect_on_failure and test_url
+        // ignore: tidy
         let title = "title:";
         let body = "body:";
         let date = "date:";
@@ -70,33 +216,54 @@ impl ConfigEntry {
         let strip = "strip:";
         let strip_id = "strip_id_or_class:";
         let strip_img = "strip_image_src:";
+        let strip_attribute = "strip_attr:";
+        let lazy_load_attr = "src_lazy_load_attr:";
         let single_page = "single_page_link:";
         let next_page = "next_page_link:";
+        let page_contains = "if_page_contains:";
         let find = "find_string:";
         let replace = "replace_string:";
         let replace_single = "replace_string(";
         let http_header = "http_header(";
+        let wrap_in = "wrap_in(";
+        let dissolve = "dissolve:";
+        let move_into = "move_into(";
+        let test_url = "test_url:";
+        let test_contains = "test_contains:";
+        let autodetect = "autodetect_on_failure:";
+        let prune_body = "prune:";
+        let skip_json = "skip_json_ld:";
+        let time_zone = "timezone:";
 
         // ignore these
         let tidy = "tidy:";
-        let prune = "prune:";
-        let test_url = "test_url:";
-        let autodetect = "autodetect_on_failure:";
 
-        let mut lines = buffer.lines();
+        let mut lines = buffer.lines().enumerate().peekable();
 
-        while let Some(Ok(line)) = lines.next() {
+        while let Some((index, Ok(line))) = lines.next() {
+            let line_number = index + 1;
             let line = line.trim();
             if line.starts_with('#')
                 || line.starts_with(tidy)
-                || line.starts_with(prune)
-                || line.starts_with(test_url)
-                || line.starts_with(autodetect)
+                || UNSUPPORTED_DIRECTIVES
+                    .iter()
+                    .any(|directive| line.starts_with(directive))
                 || line.is_empty()
             {
                 continue;
             }
 
+            // libxml panics on xpaths that can't be passed as C string
+            if line.contains('\0') {
+                linter.report(line_number, line, "contains a NUL byte");
+                continue;
+            }
+
+            linter.check_xpaths(line_number, line);
+
+            // any other directive ends the conditions of a page link rule
+            let previous_page_link_single = last_page_link_single.take();
+
             extract_vec_multi!(line, title, xpath_title);
             extract_vec_multi!(line, body, xpath_body);
             extract_vec_multi!(line, date, xpath_date);
@@ -105,14 +272,50 @@ impl ConfigEntry {
             extract_vec_single!(line, strip, xpath_strip);
             extract_vec_single!(line, strip_id, strip_id_or_class);
             extract_vec_single!(line, strip_img, strip_image_src);
+            extract_vec_single!(line, strip_attribute, strip_attr);
+
+            extract_option_single!(line, lazy_load_attr, src_lazy_load_attr);
+
+            if line.starts_with(single_page) {
+                let value = Util::str_extract_value(single_page, line);
+                single_page_link.push(PageLink::new(value));
+                last_page_link_single = Some(true);
+                continue;
+            }
+
+            if line.starts_with(next_page) {
+                let value = Util::str_extract_value(next_page, line);
+                next_page_link.push(PageLink::new(value));
+                last_page_link_single = Some(false);
+                continue;
+            }
 
-            extract_option_single!(line, single_page, single_page_link);
-            extract_option_single!(line, next_page, next_page_link);
+            if line.starts_with(page_contains) {
+                let value = Util::str_extract_value(page_contains, line);
+                last_page_link_single = previous_page_link_single;
+                let rule = match last_page_link_single {
+                    Some(true) => single_page_link.last_mut(),
+                    Some(false) => next_page_link.last_mut(),
+                    None => None,
+                };
+                match rule {
+                    Some(rule) => rule.condition = Some(value.to_string()),
+                    None => linter.report(
+                        line_number,
+                        line,
+                        "doesn't directly follow a single_page_link or next_page_link",
+                    ),
+                }
+                continue;
+            }
+
+            extract_vec_single!(line, dissolve, dissolve_vec);
 
             if line.starts_with(replace_single) {
                 let value = Util::str_extract_value(replace_single, line);
                 let value: Vec<&str> = value.split("): ").map(|s| s.trim()).collect();
                 if value.len() != 2 {
+                    linter.report(line_number, line, "expected '(<name>): <value>'");
                     continue;
                 }
 
@@ -132,6 +335,7 @@ impl ConfigEntry {
                 let value = Util::str_extract_value(http_header, line);
                 let value: Vec<&str> = value.split("): ").map(|s| s.trim()).collect();
                 if value.len() != 2 {
+                    linter.report(line_number, line, "expected '(<name>): <value>'");
                     continue;
                 }
 
@@ -147,23 +351,127 @@ impl ConfigEntry {
                 continue;
             }
 
+            if line.starts_with(wrap_in) {
+                let value = Util::str_extract_value(wrap_in, line);
+                match value.split_once("):") {
+                    Some((tag, xpath)) => wrap_in_vec.push(WrapIn {
+                        tag: tag.trim().to_string(),
+                        xpath: xpath.trim().to_string(),
+                    }),
+                    None => linter.report(line_number, line, "expected '(<tag>): <xpath>'"),
+                }
+
+                continue;
+            }
+
+            if line.starts_with(move_into) {
+                let value = Util::str_extract_value(move_into, line);
+                match value.split_once("):") {
+                    Some((target, xpath)) => move_into_vec.push(MoveInto {
+                        target: target.trim().to_string(),
+                        xpath: xpath.trim().to_string(),
+                    }),
+                    None => linter.report(line_number, line, "expected '(<xpath>): <xpath>'"),
+                }
+
+                continue;
+            }
+
+            if line.starts_with(test_url) {
+                let value = Util::str_extract_value(test_url, line);
+                test_url_vec.push(TestUrl {
+                    url: value.to_string(),
+                    contains: Vec::new(),
+                });
+                continue;
+            }
+
+            if line.starts_with(test_contains) {
+                let value = Util::str_extract_value(test_contains, line);
+                match test_url_vec.last_mut() {
+                    Some(test_url) => test_url.contains.push(value.to_string()),
+                    None => linter.report(line_number, line, "doesn't follow a test_url"),
+                }
+                continue;
+            }
+
+            if line.starts_with(autodetect) {
+                let value = Util::str_extract_value(autodetect, line);
+                match Self::parse_bool(value) {
+                    Some(value) => autodetect_on_failure = Some(value),
+                    None => linter.report(line_number, line, "expected 'yes' or 'no'"),
+                }
+                continue;
+            }
+
+            if line.starts_with(prune_body) {
+                let value = Util::str_extract_value(prune_body, line);
+                match Self::parse_bool(value) {
+                    Some(value) => prune = Some(value),
+                    None => linter.report(line_number, line, "expected 'yes' or 'no'"),
+                }
+                continue;
+            }
+
+            if line.starts_with(skip_json) {
+                let value = Util::str_extract_value(skip_json, line);
+                match Self::parse_bool(value) {
+                    Some(value) => skip_json_ld = value,
+                    None => linter.report(line_number, line, "expected 'yes' or 'no'"),
+                }
+                continue;
+            }
+
+            if line.starts_with(time_zone) {
+                let value = Util::str_extract_value(time_zone, line);
+                if parse_time_zone(value).is_some() {
+                    timezone = Some(value.trim().to_string());
+                } else {
+                    linter.report(
+                        line_number,
+                        line,
+                        "expected a time zone like 'Europe/Berlin' or '+01:00'",
+                    );
+                }
+                continue;
+            }
+
             if line.starts_with(find) {
                 let to_replace = Util::str_extract_value(find, line).into();
 
-                if let Some(Ok(next_line)) = lines.next() {
-                    let replace_with = Util::str_extract_value(replace, &next_line).into();
+                let next_line = lines
+                    .next_if(|(_, next_line)| {
+                        next_line
+                            .as_ref()
+                            .is_ok_and(|next_line| next_line.trim().starts_with(replace))
+                    })
+                    .and_then(|(_, next_line)| next_line.ok());
 
-                    replace_vec.push(Replace {
-                        to_replace,
-                        replace_with,
-                    });
+                match next_line {
+                    Some(next_line) => {
+                        let replace_with =
+                            Util::str_extract_value(replace, next_line.trim()).into();
+
+                        replace_vec.push(Replace {
+                            to_replace,
+                            replace_with,
+                        });
+                    }
+                    None => linter.report(line_number, line, "not followed by replace_string:"),
                 }
 
                 continue;
             }
+
+            if line.starts_with(replace) {
+                linter.report(line_number, line, "doesn't follow a find_string:");
+                continue;
+            }
+
+            linter.report(line_number, line, "unknown directive");
         }
 
-        let config = ConfigEntry {
+        ConfigEntry {
             xpath_title,
             xpath_author,
             xpath_date,
@@ -171,12 +479,301 @@ impl ConfigEntry {
             xpath_strip,
             strip_id_or_class,
             strip_image_src,
+            strip_attr,
+            src_lazy_load_attr,
             replace: replace_vec,
             header: header_vec,
             single_page_link,
             next_page_link,
+            wrap_in: wrap_in_vec,
+            dissolve: dissolve_vec,
+            move_into: move_into_vec,
+            test_url: test_url_vec,
+            autodetect_on_failure,
+            prune,
+            skip_json_ld,
+            timezone,
+        }
+    }
+
+    fn parse_bool(value: &str) -> Option<bool> {
+        match value.trim().to_lowercase().as_str() {
+            "yes" | "true" => Some(true),
+            "no" | "false" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the problems found while parsing a config
+struct Linter {
+    file: Option<String>,
+    validate_xpath: bool,
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl Linter {
+    fn new(file: Option<String>, validate_xpath: bool) -> Self {
+        Self {
+            file,
+            validate_xpath,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, line_number: usize, line: &str, reason: &str) {
+        self.diagnostics.push(ConfigDiagnostic {
+            file: self.file.clone(),
+            line: line_number,
+            directive: Self::directive(line).into(),
+            reason: reason.into(),
+        });
+    }
+
+    fn log(&self) {
+        for diagnostic in &self.diagnostics {
+            log::debug!("{diagnostic}");
+        }
+    }
+
+    /// Name of the directive of `line`, e.g. `replace_string` or `body`
+    fn directive(line: &str) -> &str {
+        let end = line.find([':', '(']).unwrap_or(line.len());
+        line[..end].trim()
+    }
+
+    fn check_xpaths(&mut self, line_number: usize, line: &str) {
+        if !self.validate_xpath {
+            return;
+        }
+
+        let directive = Self::directive(line);
+        let value = Util::str_extract_value(directive, line);
+        let xpaths: Vec<&str> = match directive {
+            "title" | "body" | "date" | "author" | "strip" | "strip_attr" | "dissolve"
+            | "single_page_link" | "next_page_link" | "if_page_contains" => value
+                .strip_prefix(':')
+                .map(|xpath| vec![xpath.trim()])
+                .unwrap_or_default(),
+            "wrap_in" => value
+                .strip_prefix('(')
+                .and_then(|value| value.split_once("):"))
+                .map(|(_tag, xpath)| vec![xpath.trim()])
+                .unwrap_or_default(),
+            "move_into" => value
+                .strip_prefix('(')
+                .and_then(|value| value.split_once("):"))
+                .map(|(target, xpath)| vec![target.trim(), xpath.trim()])
+                .unwrap_or_default(),
+            _ => Vec::new(),
         };
 
-        Ok(config)
+        for xpath in xpaths {
+            if xpath.is_empty() {
+                self.report(line_number, line, "missing xpath");
+            } else if !libxml::xpath::is_well_formed_xpath(xpath) {
+                self.report(line_number, line, &format!("invalid xpath '{xpath}'"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigEntry, PageLink, TestUrl};
+    use crate::full_text_parser::config::ConfigError;
+    use std::borrow::Cow;
+
+    fn parse(config: &'static str) -> ConfigEntry {
+        ConfigEntry::parse_data(Cow::Borrowed(config.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn restructuring_directives() {
+        let config = parse(
+            r#"
+wrap_in(blockquote): //div[contains(@class, 'quoteWrapper')]
+dissolve: //ul[@class='video-gallery']
+move_into(//p[../@class = 'entry-content'][position() = last()])://div[@id = 'featbox']
+"#,
+        );
+
+        assert_eq!(config.wrap_in.len(), 1);
+        assert_eq!(config.wrap_in[0].tag, "blockquote");
+        assert_eq!(
+            config.wrap_in[0].xpath,
+            "//div[contains(@class, 'quoteWrapper')]"
+        );
+        assert_eq!(config.dissolve, vec!["//ul[@class='video-gallery']"]);
+        assert_eq!(config.move_into.len(), 1);
+        assert_eq!(
+            config.move_into[0].target,
+            "//p[../@class = 'entry-content'][position() = last()]"
+        );
+        assert_eq!(config.move_into[0].xpath, "//div[@id = 'featbox']");
+    }
+
+    #[test]
+    fn attribute_directives() {
+        let config = parse(
+            r#"
+strip_attr: //img/@srcset
+strip_attr: //iframe/@srcdoc
+src_lazy_load_attr: data-lazy-src
+"#,
+        );
+
+        assert_eq!(config.strip_attr, vec!["//img/@srcset", "//iframe/@srcdoc"]);
+        assert_eq!(config.src_lazy_load_attr.as_deref(), Some("data-lazy-src"));
+    }
+
+    #[test]
+    fn conditional_page_links() {
+        let config = parse(
+            r#"
+single_page_link: //a[@class='print']
+if_page_contains: //div[@id='nextpage']
+single_page_link: //link[@rel='amphtml']/@href
+next_page_link: //a[@rel='next']
+if_page_contains: //div[@class='pager']
+"#,
+        );
+
+        assert_eq!(
+            config.single_page_link,
+            vec![
+                PageLink {
+                    xpath: "//a[@class='print']".into(),
+                    condition: Some("//div[@id='nextpage']".into()),
+                },
+                PageLink::new("//link[@rel='amphtml']/@href"),
+            ]
+        );
+        assert_eq!(
+            config.next_page_link,
+            vec![PageLink {
+                xpath: "//a[@rel='next']".into(),
+                condition: Some("//div[@class='pager']".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn orphaned_page_link_condition() {
+        let config = r#"
+single_page_link: //a[@class='print']
+strip: //div[@class='ad']
+if_page_contains: //div[@id='nextpage']
+"#;
+
+        let (config, diagnostics) = ConfigEntry::lint_data(config.as_bytes(), "example.com.txt");
+        assert_eq!(
+            config.single_page_link,
+            vec![PageLink::new("//a[@class='print']")]
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 4);
+        assert_eq!(diagnostics[0].directive, "if_page_contains");
+    }
+
+    #[test]
+    fn test_urls() {
+        let config = parse(
+            r#"
+test_url: https://example.com/article
+test_contains: First sentence
+test_contains: Second sentence
+test_url: https://example.com/other
+"#,
+        );
+
+        assert_eq!(
+            config.test_url,
+            vec![
+                TestUrl {
+                    url: "https://example.com/article".into(),
+                    contains: vec!["First sentence".into(), "Second sentence".into()],
+                },
+                TestUrl {
+                    url: "https://example.com/other".into(),
+                    contains: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_diagnostics() {
+        let config = r#"
+# comment
+titl: //h1
+body: //div[@id='content'
+replace_string(<br>)<p>
+find_string: <br>
+body: //article
+"#;
+
+        let (config, diagnostics) = ConfigEntry::lint_data(config.as_bytes(), "example.com.txt");
+        let diagnostics: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.line, d.directive.as_str(), d.reason.as_str()))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (3, "titl", "unknown directive"),
+                (4, "body", "invalid xpath '//div[@id='content''"),
+                (5, "replace_string", "expected '(<name>): <value>'"),
+                (6, "find_string", "not followed by replace_string:"),
+            ]
+        );
+        assert_eq!(config.xpath_body.len(), 2);
+
+        let (config, diagnostics) = ConfigEntry::lint_data(b"body: //div\0", "example.com.txt");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].reason, "contains a NUL byte");
+        assert!(config.xpath_body.is_empty());
+
+        let error = ConfigEntry::parse_data_strict(b"titl: //h1", "example.com.txt").unwrap_err();
+        assert!(matches!(error, ConfigError::Invalid(diagnostics) if diagnostics.len() == 1));
+        assert!(ConfigEntry::parse_data_strict(b"title: //h1", "example.com.txt").is_ok());
+    }
+
+    #[test]
+    fn autodetect_on_failure() {
+        assert!(parse("body: //article").autodetect_on_failure());
+        assert!(!parse("autodetect_on_failure: no").autodetect_on_failure());
+        assert!(parse("autodetect_on_failure: yes").autodetect_on_failure());
+    }
+
+    #[test]
+    fn prune() {
+        assert!(parse("body: //article").prune());
+        assert!(!parse("prune: no").prune());
+    }
+
+    #[test]
+    fn skip_json_ld() {
+        assert!(!parse("body: //article").skip_json_ld);
+        assert!(parse("skip_json_ld: yes").skip_json_ld);
+        assert!(parse("skip_json_ld: true").skip_json_ld);
+    }
+
+    #[test]
+    fn timezone() {
+        assert_eq!(
+            parse("timezone: +01:00").timezone.as_deref(),
+            Some("+01:00")
+        );
+        assert_eq!(
+            parse("timezone: Europe/Berlin").timezone.as_deref(),
+            Some("Europe/Berlin")
+        );
+        assert_eq!(parse("timezone: Mars/Olympus_Mons").timezone, None);
+
+        let (_, diagnostics) =
+            ConfigEntry::lint_data(b"timezone: Mars/Olympus_Mons", "example.com.txt");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].directive, "timezone");
     }
 }
@@ -1,9 +1,32 @@
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("IO error")]
     IO(#[from] std::io::Error),
+    #[error("Invalid config: {}", .0.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("; "))]
+    Invalid(Vec<ConfigDiagnostic>),
     #[error("Unknown Error")]
     Unknown,
 }
+
+/// A problem found on a single line of a site config
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigDiagnostic {
+    /// File name of the config, if known
+    pub file: Option<String>,
+    /// 1-based line number
+    pub line: usize,
+    pub directive: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        write!(f, "{}: {}: {}", self.line, self.directive, self.reason)
+    }
+}
@@ -5,5 +5,5 @@ mod config_entry;
 mod error;
 
 pub use config_collection::ConfigCollection;
-pub use config_entry::ConfigEntry;
-pub use error::ConfigError;
+pub use config_entry::{ConfigEntry, Header, MoveInto, PageLink, TestUrl, WrapIn};
+pub use error::{ConfigDiagnostic, ConfigError};
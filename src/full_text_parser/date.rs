@@ -0,0 +1,458 @@
+use crate::constants;
+use chrono::{
+    DateTime, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone as _, Utc,
+};
+use chrono_tz::Tz;
+
+/// Formats of timestamps without timezone information
+const NAIVE_DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%Y, %H:%M",
+];
+
+const NAIVE_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y", "%Y%m%d"];
+
+/// Formats with a numeric offset that are not valid RFC 3339
+const DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f %z",
+    "%Y-%m-%dT%H:%M%z",
+];
+
+/// Full and abbreviated month names in English, German, French, Spanish, Italian,
+/// Dutch and Portuguese
+const MONTHS: &[(&str, u32)] = &[
+    ("january", 1),
+    ("jan", 1),
+    ("januar", 1),
+    ("jänner", 1),
+    ("janvier", 1),
+    ("janv", 1),
+    ("enero", 1),
+    ("ene", 1),
+    ("gennaio", 1),
+    ("gen", 1),
+    ("januari", 1),
+    ("janeiro", 1),
+    ("february", 2),
+    ("feb", 2),
+    ("februar", 2),
+    ("février", 2),
+    ("fevrier", 2),
+    ("févr", 2),
+    ("febrero", 2),
+    ("febbraio", 2),
+    ("februari", 2),
+    ("fevereiro", 2),
+    ("fev", 2),
+    ("march", 3),
+    ("mar", 3),
+    ("märz", 3),
+    ("maerz", 3),
+    ("mär", 3),
+    ("mars", 3),
+    ("marzo", 3),
+    ("maart", 3),
+    ("março", 3),
+    ("marco", 3),
+    ("april", 4),
+    ("apr", 4),
+    ("avril", 4),
+    ("avr", 4),
+    ("abril", 4),
+    ("abr", 4),
+    ("aprile", 4),
+    ("may", 5),
+    ("mai", 5),
+    ("mayo", 5),
+    ("maggio", 5),
+    ("mag", 5),
+    ("mei", 5),
+    ("maio", 5),
+    ("june", 6),
+    ("jun", 6),
+    ("juni", 6),
+    ("juin", 6),
+    ("junio", 6),
+    ("giugno", 6),
+    ("giu", 6),
+    ("junho", 6),
+    ("july", 7),
+    ("jul", 7),
+    ("juli", 7),
+    ("juillet", 7),
+    ("juil", 7),
+    ("julio", 7),
+    ("luglio", 7),
+    ("lug", 7),
+    ("julho", 7),
+    ("august", 8),
+    ("aug", 8),
+    ("août", 8),
+    ("aout", 8),
+    ("agosto", 8),
+    ("ago", 8),
+    ("augustus", 8),
+    ("september", 9),
+    ("sep", 9),
+    ("sept", 9),
+    ("septembre", 9),
+    ("septiembre", 9),
+    ("setiembre", 9),
+    ("settembre", 9),
+    ("set", 9),
+    ("setembro", 9),
+    ("october", 10),
+    ("oct", 10),
+    ("oktober", 10),
+    ("okt", 10),
+    ("octobre", 10),
+    ("octubre", 10),
+    ("ottobre", 10),
+    ("ott", 10),
+    ("outubro", 10),
+    ("out", 10),
+    ("november", 11),
+    ("nov", 11),
+    ("novembre", 11),
+    ("noviembre", 11),
+    ("novembro", 11),
+    ("december", 12),
+    ("dec", 12),
+    ("dezember", 12),
+    ("dez", 12),
+    ("décembre", 12),
+    ("déc", 12),
+    ("decembre", 12),
+    ("diciembre", 12),
+    ("dic", 12),
+    ("dicembre", 12),
+    ("dezembro", 12),
+];
+
+/// Time zone of the `timezone:` directive of a site config
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeZone {
+    Iana(Tz),
+    Fixed(FixedOffset),
+}
+
+static UTC: TimeZone = TimeZone::Iana(Tz::UTC);
+
+/// Parse the date strings commonly found in web pages: RFC 3339, RFC 2822, ISO 8601
+/// variants, unix timestamps and dates with month names such as `October 17, 2026`
+/// or `17. Oktober 2026`.
+///
+/// Dates without timezone are interpreted in `time_zone`, or UTC if it is `None`.
+pub fn parse_date(date: &str, time_zone: Option<&TimeZone>) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    if date.is_empty() {
+        return None;
+    }
+
+    let time_zone = time_zone.unwrap_or(&UTC);
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc2822(date) {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    for format in DATE_TIME_FORMATS {
+        if let Ok(date) = DateTime::parse_from_str(date, format) {
+            return Some(date.with_timezone(&Utc));
+        }
+    }
+
+    // `Z` suffix without seconds or with a space, e.g. `2023-03-01 10:00:00Z`
+    if let Some(naive) = date.strip_suffix('Z').or_else(|| date.strip_suffix(" UTC")) {
+        if let Some(date) = parse_naive(naive, &UTC) {
+            return Some(date);
+        }
+    }
+
+    if let Some(date) = parse_naive(date, time_zone) {
+        return Some(date);
+    }
+
+    if let Some(date) = parse_timestamp(date) {
+        return Some(date);
+    }
+
+    parse_month_name(date, time_zone)
+}
+
+/// Parse a `timezone:` value: an IANA time zone such as `Europe/Berlin`, a fixed offset
+/// such as `+01:00` or `-0500`, or `UTC`
+pub fn parse_time_zone(time_zone: &str) -> Option<TimeZone> {
+    let time_zone = time_zone.trim();
+    if time_zone.eq_ignore_ascii_case("utc") || time_zone.eq_ignore_ascii_case("gmt") {
+        return Some(UTC);
+    }
+
+    let (sign, offset) = if let Some(offset) = time_zone.strip_prefix('+') {
+        (1, offset)
+    } else if let Some(offset) = time_zone.strip_prefix('-') {
+        (-1, offset)
+    } else {
+        return time_zone.parse().ok().map(TimeZone::Iana);
+    };
+    let offset = offset.replace(':', "");
+    if offset.len() != 4 || !offset.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let hours: i32 = offset[..2].parse().ok()?;
+    let minutes: i32 = offset[2..].parse().ok()?;
+    let offset = FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))?;
+    Some(TimeZone::Fixed(offset))
+}
+
+/// Interpret `naive` as local time of `time_zone`. Times skipped or repeated by a
+/// daylight saving time transition resolve like in JavaScript and `java.time`: the
+/// earlier of repeated times, skipped times are moved forward by the length of the gap.
+fn localize(naive: NaiveDateTime, time_zone: &TimeZone) -> Option<DateTime<Utc>> {
+    let tz = match time_zone {
+        TimeZone::Iana(tz) => tz,
+        TimeZone::Fixed(offset) => {
+            return offset
+                .from_local_datetime(&naive)
+                .single()
+                .map(|date| date.with_timezone(&Utc))
+        }
+    };
+
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(date) | LocalResult::Ambiguous(date, _) => {
+            Some(date.with_timezone(&Utc))
+        }
+        LocalResult::None => {
+            // the offset a day before is the one in effect before the gap
+            let before = tz
+                .offset_from_local_datetime(&(naive - Duration::days(1)))
+                .earliest()?
+                .fix();
+            Some(Utc.from_utc_datetime(&(naive - before)))
+        }
+    }
+}
+
+fn parse_naive(date: &str, time_zone: &TimeZone) -> Option<DateTime<Utc>> {
+    let naive = NAIVE_DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .or_else(|| {
+            NAIVE_DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+
+    localize(naive, time_zone)
+}
+
+/// Unix timestamps in seconds or milliseconds
+fn parse_timestamp(date: &str) -> Option<DateTime<Utc>> {
+    if !date.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let timestamp: i64 = date.parse().ok()?;
+    match date.len() {
+        9 | 10 => DateTime::from_timestamp(timestamp, 0),
+        12 | 13 => DateTime::from_timestamp_millis(timestamp),
+        _ => None,
+    }
+}
+
+/// Words that may stand between the day, month and year, e.g. `17 de octubre de 2026`
+/// or `17th of October`
+const DATE_FILLERS: &[&str] = &["de", "del", "di", "of", "st", "nd", "rd", "th", "er"];
+
+/// Parse a date with a month name. Month names only count next to the day or year, so
+/// words like "ago" or "set" elsewhere in the string aren't taken for a month. Longer
+/// names are preferred if there are several.
+fn parse_month_name(date: &str, time_zone: &TimeZone) -> Option<DateTime<Utc>> {
+    let date = date.to_lowercase();
+    let tokens: Vec<&str> = constants::DATE_TOKEN
+        .find_iter(&date)
+        .map(|token| token.as_str())
+        .filter(|token| !DATE_FILLERS.contains(token))
+        .collect();
+
+    let is_day = |index: usize| {
+        tokens
+            .get(index)
+            .is_some_and(|token| token.len() <= 2 && token.chars().all(|c| c.is_ascii_digit()))
+    };
+    let is_year = |index: usize| {
+        tokens
+            .get(index)
+            .is_some_and(|token| token.len() == 4 && token.chars().all(|c| c.is_ascii_digit()))
+    };
+
+    let mut months: Vec<(usize, u32)> = tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(index, token)| {
+            let (_, month) = MONTHS.iter().find(|(name, _)| name == token)?;
+            Some((index, *month))
+        })
+        .filter(|&(index, _)| {
+            is_day(index + 1) || is_year(index + 1) || index.checked_sub(1).is_some_and(is_day)
+        })
+        .collect();
+    months.sort_by_key(|&(index, _)| std::cmp::Reverse(tokens[index].len()));
+    let &(month_index, month) = months.first()?;
+
+    // `October 17` or `17 October`
+    let day_index = if is_day(month_index + 1) {
+        month_index + 1
+    } else {
+        month_index.checked_sub(1).filter(|&index| is_day(index))?
+    };
+    let day: u32 = tokens[day_index].parse().ok()?;
+
+    let year_index = (month_index + 1..tokens.len())
+        .find(|&index| is_year(index))
+        .or_else(|| (0..month_index).rev().find(|&index| is_year(index)))?;
+    let year: i32 = tokens[year_index].parse().ok()?;
+
+    let mut time = None;
+    let mut pm = None;
+    let mut utc = false;
+    for token in &tokens {
+        match *token {
+            "am" => pm = Some(false),
+            "pm" => pm = Some(true),
+            "utc" | "gmt" | "z" => utc = true,
+            token if token.contains(':') && time.is_none() => {
+                let mut parts = token.split(':').filter_map(|part| part.parse::<u32>().ok());
+                let hour = parts.next()?;
+                let minute = parts.next()?;
+                let second = parts.next().unwrap_or(0);
+                time = Some((hour, minute, second));
+            }
+            _ => {}
+        }
+    }
+
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+    let (mut hour, minute, second) = time.unwrap_or((0, 0, 0));
+    match pm {
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    let naive = date.and_time(NaiveTime::from_hms_opt(hour, minute, second)?);
+
+    localize(naive, if utc { &UTC } else { time_zone })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_date, parse_time_zone};
+
+    fn parse(date: &str) -> Option<String> {
+        parse_date(date, None).map(|date| date.to_rfc3339())
+    }
+
+    #[test]
+    fn machine_formats() {
+        let expected = Some("2026-10-17T08:30:00+00:00".to_string());
+        assert_eq!(parse("2026-10-17T10:30:00+02:00"), expected);
+        assert_eq!(parse("2026-10-17T10:30:00+0200"), expected);
+        assert_eq!(parse("Sat, 17 Oct 2026 10:30:00 +0200"), expected);
+        assert_eq!(parse("2026-10-17 08:30:00"), expected);
+        assert_eq!(parse("2026-10-17T08:30"), expected);
+        assert_eq!(parse("17.10.2026 08:30"), expected);
+        assert_eq!(parse("1792225800"), expected);
+        assert_eq!(parse("1792225800000"), expected);
+        assert_eq!(
+            parse("2026-10-17"),
+            Some("2026-10-17T00:00:00+00:00".into())
+        );
+    }
+
+    #[test]
+    fn month_names() {
+        let expected = Some("2026-10-17T00:00:00+00:00".to_string());
+        assert_eq!(parse("October 17, 2026"), expected);
+        assert_eq!(parse("Saturday, Oct 17th 2026"), expected);
+        assert_eq!(parse("17. Oktober 2026"), expected);
+        assert_eq!(parse("17 octobre 2026"), expected);
+        assert_eq!(parse("17 de octubre de 2026"), expected);
+        assert_eq!(parse("17 ottobre 2026"), expected);
+        assert_eq!(
+            parse("October 17, 2026 at 8:30 pm"),
+            Some("2026-10-17T20:30:00+00:00".into())
+        );
+        assert_eq!(parse("no date"), None);
+    }
+
+    #[test]
+    fn month_name_words() {
+        let expected = Some("2026-10-17T00:00:00+00:00".to_string());
+        assert_eq!(parse("Updated 2 days ago, October 17, 2026"), expected);
+        assert_eq!(parse("Set for release 17 October 2026"), expected);
+        assert_eq!(parse("17th of October, 2026"), expected);
+        assert_eq!(parse("May we see you on 17 Oct 2026"), expected);
+        assert_eq!(parse("Out now"), None);
+    }
+
+    #[test]
+    fn default_time_zone() {
+        let parse_in = |date: &str, time_zone: &str| {
+            parse_date(date, parse_time_zone(time_zone).as_ref()).map(|date| date.to_rfc3339())
+        };
+
+        assert_eq!(
+            parse_in("2026-10-17 10:30", "+02:00"),
+            Some("2026-10-17T08:30:00+00:00".into())
+        );
+        assert_eq!(
+            parse_in("2026-10-17T10:30:00Z", "+02:00"),
+            Some("2026-10-17T10:30:00+00:00".into())
+        );
+        assert_eq!(
+            parse_in("2026-10-17 10:30", "-0500"),
+            Some("2026-10-17T15:30:00+00:00".into())
+        );
+        assert_eq!(
+            parse_in("2026-10-17 10:30", "UTC"),
+            Some("2026-10-17T10:30:00+00:00".into())
+        );
+
+        // daylight saving time in summer, standard time in winter
+        assert_eq!(
+            parse_in("2026-10-17 10:30", "Europe/Berlin"),
+            Some("2026-10-17T08:30:00+00:00".into())
+        );
+        assert_eq!(
+            parse_in("17. Dezember 2026, 10:30", "Europe/Berlin"),
+            Some("2026-12-17T09:30:00+00:00".into())
+        );
+        // skipped and repeated times of the transitions
+        assert_eq!(
+            parse_in("2026-03-29 02:30", "Europe/Berlin"),
+            Some("2026-03-29T01:30:00+00:00".into())
+        );
+        assert_eq!(
+            parse_in("2026-10-25 02:30", "Europe/Berlin"),
+            Some("2026-10-25T00:30:00+00:00".into())
+        );
+        assert!(parse_time_zone("Mars/Olympus_Mons").is_none());
+        assert!(parse_time_zone("+2").is_none());
+    }
+}
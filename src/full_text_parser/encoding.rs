@@ -0,0 +1,99 @@
+use crate::constants;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+/// Number of bytes searched for a `<meta charset>` declaration
+const META_PRESCAN_LENGTH: usize = 4096;
+
+/// Decode the raw bytes of an HTML page.
+///
+/// The encoding is taken from the first of: the byte order mark, the charset of the
+/// `Content-Type` header in `content_type`, a `<meta charset>` or `<meta http-equiv>`
+/// declaration. Without any of those the page is decoded as UTF-8 if it is valid UTF-8
+/// and as windows-1252 otherwise.
+pub fn decode_html(html: &[u8], content_type: Option<&str>) -> (String, &'static Encoding) {
+    let encoding = sniff_encoding(html, content_type);
+    // decode() strips the BOM and replaces invalid sequences
+    let (decoded, encoding, had_errors) = encoding.decode(html);
+    if had_errors {
+        log::debug!("HTML contains invalid {} sequences", encoding.name());
+    }
+    (decoded.into_owned(), encoding)
+}
+
+fn sniff_encoding(html: &[u8], content_type: Option<&str>) -> &'static Encoding {
+    if let Some((encoding, _bom_length)) = Encoding::for_bom(html) {
+        return encoding;
+    }
+
+    if let Some(encoding) = content_type.and_then(content_type_charset) {
+        return encoding;
+    }
+
+    if let Some(encoding) = meta_charset(html) {
+        return encoding;
+    }
+
+    if std::str::from_utf8(html).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// `text/html; charset=Shift_JIS`
+fn content_type_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(name, _value)| name.trim().eq_ignore_ascii_case("charset"))
+        .and_then(|(_name, value)| Encoding::for_label(value.trim().trim_matches('"').as_bytes()))
+}
+
+fn meta_charset(html: &[u8]) -> Option<&'static Encoding> {
+    let prefix = String::from_utf8_lossy(&html[..html.len().min(META_PRESCAN_LENGTH)]);
+    let label = constants::META_CHARSET.captures(&prefix)?.get(1)?.as_str();
+    let encoding = Encoding::for_label(label.as_bytes())?;
+
+    // a document that can declare its encoding in ASCII isn't UTF-16
+    if encoding == UTF_16BE || encoding == UTF_16LE {
+        Some(UTF_8)
+    } else {
+        Some(encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_html;
+    use encoding_rs::{SHIFT_JIS, UTF_8, WINDOWS_1251, WINDOWS_1252};
+
+    #[test]
+    fn sniff_encoding() {
+        let html = "<html><head><meta charset=\"windows-1251\"></head><body>Привет</body></html>";
+        let (bytes, _, _) = WINDOWS_1251.encode(html);
+        assert_eq!(decode_html(&bytes, None), (html.to_string(), WINDOWS_1251));
+
+        let html = "<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=Shift_JIS\"></head><body>こんにちは</body></html>";
+        let (bytes, _, _) = SHIFT_JIS.encode(html);
+        assert_eq!(decode_html(&bytes, None), (html.to_string(), SHIFT_JIS));
+
+        // the Content-Type header overrides the document
+        let html = "<html><head><meta charset=\"utf-8\"></head><body>Привет</body></html>";
+        let (bytes, _, _) = WINDOWS_1251.encode(html);
+        let content_type = Some("text/html; charset=\"windows-1251\"");
+        assert_eq!(decode_html(&bytes, content_type).1, WINDOWS_1251);
+
+        // the BOM overrides everything
+        let bytes = [b"\xEF\xBB\xBF".as_slice(), "<p>Grüße</p>".as_bytes()].concat();
+        let content_type = Some("text/html; charset=iso-8859-1");
+        assert_eq!(
+            decode_html(&bytes, content_type),
+            ("<p>Grüße</p>".to_string(), UTF_8)
+        );
+
+        // fallbacks without any declaration
+        assert_eq!(decode_html("<p>Grüße</p>".as_bytes(), None).1, UTF_8);
+        assert_eq!(decode_html(b"<p>Gr\xFC\xDFe</p>", None).1, WINDOWS_1252);
+    }
+}
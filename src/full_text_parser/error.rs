@@ -20,6 +20,8 @@ pub enum FullTextParserError {
     Utf8(#[from] std::str::Utf8Error),
     #[error("Readability Error")]
     Readability,
+    #[error("No body found and autodetect_on_failure is disabled")]
+    AutodetectDisabled,
     #[error("Unknown Error")]
     Unknown,
 }
@@ -0,0 +1,173 @@
+use crate::{article::Author, constants, util::Util};
+use libxml::xpath::Context;
+use serde_json::Value;
+
+/// Article metadata of a `<script type="application/ld+json">` block
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct JsonLd {
+    pub title: Option<String>,
+    pub authors: Vec<Author>,
+    pub date_published: Option<String>,
+    pub date_modified: Option<String>,
+    pub image: Option<String>,
+    pub publisher: Option<String>,
+}
+
+impl JsonLd {
+    /// Metadata of the first schema.org article found in the JSON-LD blocks of the page
+    pub fn parse(context: &Context) -> Option<Self> {
+        let scripts =
+            Util::evaluate_xpath(context, "//script[@type='application/ld+json']", false).ok()?;
+
+        scripts.iter().find_map(|script| {
+            let content = script.get_content();
+            // some sites wrap the JSON in CDATA comments
+            let content = content
+                .trim()
+                .trim_start_matches("<![CDATA[")
+                .trim_end_matches("]]>");
+
+            match serde_json::from_str::<Value>(content) {
+                Ok(value) => Self::find_article(&value).map(Self::from_article),
+                Err(error) => {
+                    log::debug!("Failed to parse JSON-LD: {error}");
+                    None
+                }
+            }
+        })
+    }
+
+    fn find_article(value: &Value) -> Option<&Value> {
+        match value {
+            Value::Array(values) => values.iter().find_map(Self::find_article),
+            Value::Object(object) => {
+                if let Some(graph) = object.get("@graph") {
+                    if let Some(article) = Self::find_article(graph) {
+                        return Some(article);
+                    }
+                }
+
+                let is_article = match object.get("@type") {
+                    Some(Value::String(name)) => constants::JSON_LD_ARTICLE_TYPES.is_match(name),
+                    Some(Value::Array(names)) => names
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .any(|name| constants::JSON_LD_ARTICLE_TYPES.is_match(name)),
+                    _ => false,
+                };
+                is_article.then_some(value)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_article(article: &Value) -> Self {
+        let title =
+            Self::string(article.get("headline")).or_else(|| Self::string(article.get("name")));
+
+        let authors = match article.get("author") {
+            Some(Value::Array(authors)) => authors.iter().filter_map(Self::author).collect(),
+            Some(author) => Self::author(author).into_iter().collect(),
+            None => Vec::new(),
+        };
+
+        let image = match article.get("image") {
+            Some(Value::Array(images)) => images.iter().find_map(Self::url),
+            Some(image) => Self::url(image),
+            None => None,
+        };
+
+        Self {
+            title,
+            authors,
+            date_published: Self::string(article.get("datePublished")),
+            date_modified: Self::string(article.get("dateModified")),
+            image,
+            publisher: article.get("publisher").and_then(Self::name),
+        }
+    }
+
+    fn string(value: Option<&Value>) -> Option<String> {
+        value
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(Into::into)
+    }
+
+    /// `"name"` or `{ "name": "name" }`
+    fn name(value: &Value) -> Option<String> {
+        match value {
+            Value::Object(object) => Self::string(object.get("name")),
+            value => Self::string(Some(value)),
+        }
+    }
+
+    /// `"name"` or `{ "name": "name", "url": "url" }`, falling back to `sameAs` for the url
+    fn author(value: &Value) -> Option<Author> {
+        let mut author = Author::new(Self::name(value)?);
+        if let Value::Object(object) = value {
+            author.url = Self::string(object.get("url")).or_else(|| match object.get("sameAs") {
+                Some(Value::Array(urls)) => urls.iter().find_map(|url| Self::string(Some(url))),
+                url => Self::string(url),
+            });
+        }
+        Some(author)
+    }
+
+    /// `"url"` or `{ "url": "url" }`
+    fn url(value: &Value) -> Option<String> {
+        match value {
+            Value::Object(object) => Self::string(object.get("url")),
+            value => Self::string(Some(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonLd;
+    use crate::article::Author;
+    use serde_json::json;
+
+    #[test]
+    fn graph_with_nested_authors() {
+        let value = json!({
+            "@context": "https://schema.org",
+            "@graph": [
+                { "@type": "WebSite", "name": "Example" },
+                {
+                    "@type": ["NewsArticle"],
+                    "headline": "The headline",
+                    "author": [
+                        { "@type": "Person", "name": "Jane Doe", "url": "https://example.com/jane" },
+                        "John Doe"
+                    ],
+                    "dateModified": "2023-03-02T08:00:00+01:00",
+                    "datePublished": "2023-03-01T10:00:00+01:00",
+                    "image": { "@type": "ImageObject", "url": "https://example.com/image.jpg" },
+                    "publisher": { "@type": "Organization", "name": "Example News" }
+                }
+            ]
+        });
+
+        let article = JsonLd::find_article(&value).map(JsonLd::from_article);
+        assert_eq!(
+            article,
+            Some(JsonLd {
+                title: Some("The headline".into()),
+                authors: vec![
+                    Author {
+                        name: "Jane Doe".into(),
+                        url: Some("https://example.com/jane".into()),
+                    },
+                    Author::new("John Doe"),
+                ],
+                date_published: Some("2023-03-01T10:00:00+01:00".into()),
+                date_modified: Some("2023-03-02T08:00:00+01:00".into()),
+                image: Some("https://example.com/image.jpg".into()),
+                publisher: Some("Example News".into()),
+            })
+        );
+    }
+}
@@ -1,9 +1,15 @@
 use super::config::ConfigEntry;
-use crate::{article::Article, constants, util::Util};
+use super::date::{self, TimeZone};
+use super::json_ld::JsonLd;
+use crate::{
+    article::{Article, Author, MetadataSource},
+    constants,
+    util::Util,
+};
 use chrono::{DateTime, Utc};
-use libxml::xpath::Context;
+use libxml::{tree::Node, xpath::Context};
 use log::{debug, warn};
-use std::str::FromStr;
+use url::Url;
 
 pub fn extract(
     context: &Context,
@@ -11,8 +17,15 @@ pub fn extract(
     global_config: Option<&ConfigEntry>,
     article: &mut Article,
 ) {
+    let json_ld = if config.is_some_and(|config| config.skip_json_ld) {
+        None
+    } else {
+        JsonLd::parse(context)
+    };
+    let json_ld = json_ld.as_ref();
+
     if article.title.is_none() {
-        article.title = extract_title(context, config, global_config)
+        article.title = extract_title(context, config, global_config, json_ld)
             .map(|title| match escaper::decode_html(&title) {
                 Ok(escaped_title) => escaped_title,
                 Err(_error) => title,
@@ -36,25 +49,93 @@ pub fn extract(
             });
     }
 
-    if article.author.is_none() {
-        article.author =
-            extract_author(context, config, global_config).map(
-                |author| match escaper::decode_html(&author) {
-                    Ok(escaped_author) => escaped_author,
-                    Err(_error) => author,
-                },
-            );
+    if article.authors.is_empty() {
+        if let Some((authors, source)) =
+            extract_authors(context, config, global_config, json_ld, &article.url)
+        {
+            article.set_authors(authors, source);
+        }
     }
 
+    let time_zone = config
+        .and_then(|config| config.timezone.as_deref())
+        .and_then(date::parse_time_zone);
+
     if article.date.is_none() {
-        article.date = extract_date(context, config, global_config);
+        article.date = extract_date(context, config, global_config, json_ld, time_zone.as_ref());
+    }
+
+    if article.date_modified.is_none() {
+        article.date_modified = extract_date_modified(context, json_ld, time_zone.as_ref());
+    }
+
+    if article.thumbnail_url.is_none() {
+        article.thumbnail_url = json_ld.and_then(|json_ld| json_ld.image.clone());
+    }
+
+    if article.excerpt.is_none() {
+        article.excerpt = get_meta_content(context, "dc:description")
+            .or_else(|| get_meta_content(context, "dcterm:description"))
+            .or_else(|| get_meta_content(context, "og:description"))
+            .or_else(|| get_meta_content(context, "weibo:article:description"))
+            .or_else(|| get_meta_content(context, "weibo:webpage:description"))
+            .or_else(|| get_meta_content(context, "description"))
+            .or_else(|| get_meta_content(context, "twitter:description"))
+            .map(|excerpt| match escaper::decode_html(&excerpt) {
+                Ok(escaped_excerpt) => escaped_excerpt,
+                Err(_error) => excerpt,
+            });
+    }
+
+    if article.site_name.is_none() {
+        article.site_name = json_ld
+            .and_then(|json_ld| json_ld.publisher.clone())
+            .or_else(|| get_meta_content(context, "og:site_name"));
+    }
+
+    if article.language.is_none() {
+        article.language = Util::get_attribute(context, "//html[@lang]", "lang")
+            .ok()
+            .or_else(|| get_meta_http_equiv(context, "content-language"))
+            .filter(|lang| !lang.trim().is_empty());
+    }
+
+    if article.direction.is_none() {
+        article.direction = Util::get_attribute(context, "//html[@dir]", "dir")
+            .or_else(|_| Util::get_attribute(context, "//body[@dir]", "dir"))
+            .ok()
+            .map(|dir| dir.trim().to_lowercase())
+            .filter(|dir| dir == "ltr" || dir == "rtl");
+    }
+}
+
+/// Metadata that depends on the extracted content: the text length and
+/// the first paragraph as excerpt if the page didn't provide a description
+pub fn extract_from_body(root: &Node, article: &mut Article) {
+    article.text_length = Util::get_inner_text(root, true).chars().count();
+
+    if article.excerpt.is_none() {
+        article.excerpt = first_paragraph(root);
+    }
+}
+
+fn first_paragraph(node: &Node) -> Option<String> {
+    if node.get_name().eq_ignore_ascii_case("p") {
+        let text = Util::get_inner_text(node, true);
+        let text = text.trim();
+        if !text.is_empty() {
+            return Some(text.to_string());
+        }
     }
+
+    node.get_child_elements().iter().find_map(first_paragraph)
 }
 
 fn extract_title(
     context: &Context,
     config: Option<&ConfigEntry>,
     global_config: Option<&ConfigEntry>,
+    json_ld: Option<&JsonLd>,
 ) -> Option<String> {
     // check site specific config
     if let Some(config) = config {
@@ -66,6 +147,12 @@ fn extract_title(
         }
     }
 
+    // check JSON-LD
+    if let Some(title) = json_ld.and_then(|json_ld| json_ld.title.clone()) {
+        debug!("Article title (JSON-LD): '{}'", title);
+        return Some(title);
+    }
+
     // check global config
     if let Some(global_config) = global_config {
         for xpath_title in &global_config.xpath_title {
@@ -87,72 +174,258 @@ fn extract_title(
         .or_else(|| get_meta(context, "twitter:title"))
 }
 
-fn extract_author(
+fn extract_authors(
     context: &Context,
     config: Option<&ConfigEntry>,
     global_config: Option<&ConfigEntry>,
-) -> Option<String> {
+    json_ld: Option<&JsonLd>,
+    base_url: &Url,
+) -> Option<(Vec<Author>, MetadataSource)> {
     // check site specific config
     if let Some(config) = config {
         for xpath_author in &config.xpath_author {
-            if let Ok(author) = Util::extract_value(context, xpath_author) {
-                debug!("Article author: '{}'", author);
-                return Some(author);
+            let authors = authors_from_xpath(context, xpath_author, base_url);
+            if !authors.is_empty() {
+                debug!("Article authors: {:?}", authors);
+                return Some((authors, MetadataSource::SiteConfig));
             }
         }
     }
 
+    // check JSON-LD
+    if let Some(json_ld) = json_ld.filter(|json_ld| !json_ld.authors.is_empty()) {
+        debug!("Article authors (JSON-LD): {:?}", json_ld.authors);
+        return Some((json_ld.authors.clone(), MetadataSource::JsonLd));
+    }
+
     // check global config
     if let Some(global_config) = global_config {
         for xpath_author in &global_config.xpath_author {
-            if let Ok(author) = Util::extract_value(context, xpath_author) {
-                debug!("Article author: '{}'", author);
-                return Some(author);
+            let authors = authors_from_xpath(context, xpath_author, base_url);
+            if !authors.is_empty() {
+                debug!("Article authors: {:?}", authors);
+                return Some((authors, MetadataSource::GlobalConfig));
             }
         }
     }
 
+    // microdata
+    let authors = authors_from_microdata(context, base_url);
+    if !authors.is_empty() {
+        debug!("Article authors (microdata): {:?}", authors);
+        return Some((authors, MetadataSource::Meta));
+    }
+
     // generic meta (readablity)
     Util::extract_value(context, "//author")
         .ok()
         .or_else(|| get_meta(context, "dc:creator"))
         .or_else(|| get_meta(context, "dcterm:creator"))
+        .map(|author| (vec![Author::new(author)], MetadataSource::Meta))
+}
+
+/// One author per node matched by `xpath`. The link of the node, or its only
+/// link, becomes the profile url of the author.
+fn authors_from_xpath(context: &Context, xpath: &str, base_url: &Url) -> Vec<Author> {
+    let Ok(nodes) = Util::evaluate_xpath(context, xpath, false) else {
+        return Vec::new();
+    };
+
+    let mut authors: Vec<Author> = Vec::new();
+    for node in nodes {
+        let Some(name) = author_name(&node.get_content()) else {
+            continue;
+        };
+        if authors.iter().any(|author| author.name == name) {
+            continue;
+        }
+
+        let url = if node.get_name().eq_ignore_ascii_case("a") {
+            node.get_attribute("href")
+        } else {
+            match node.findnodes(".//a[@href]").as_deref() {
+                Ok([link]) => link.get_attribute("href"),
+                _ => None,
+            }
+        };
+
+        authors.push(Author {
+            name,
+            url: url.and_then(|url| absolute_url(&url, base_url)),
+        });
+    }
+    authors
+}
+
+/// schema.org microdata, e.g. `<span itemprop="author"><a itemprop="url" href="..."><span itemprop="name">...</span></a></span>`
+fn authors_from_microdata(context: &Context, base_url: &Url) -> Vec<Author> {
+    let Ok(nodes) = Util::evaluate_xpath(context, "//*[@itemprop='author']", false) else {
+        return Vec::new();
+    };
+
+    let mut authors: Vec<Author> = Vec::new();
+    for node in nodes {
+        let name = match node.findnodes(".//*[@itemprop='name']").as_deref() {
+            Ok([name, ..]) => item_prop_value(name),
+            _ => item_prop_value(&node),
+        };
+        let Some(name) = author_name(&name) else {
+            continue;
+        };
+        if authors.iter().any(|author| author.name == name) {
+            continue;
+        }
+
+        let url = match node.findnodes(".//*[@itemprop='url']").as_deref() {
+            Ok([url, ..]) => url
+                .get_attribute("href")
+                .or_else(|| url.get_attribute("content")),
+            _ => node.get_attribute("href"),
+        };
+
+        authors.push(Author {
+            name,
+            url: url.and_then(|url| absolute_url(&url, base_url)),
+        });
+    }
+    authors
+}
+
+fn author_name(name: &str) -> Option<String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(match escaper::decode_html(&name) {
+        Ok(escaped_name) => escaped_name,
+        Err(_error) => name,
+    })
+}
+
+fn absolute_url(url: &str, base_url: &Url) -> Option<String> {
+    base_url.join(url.trim()).ok().map(String::from)
 }
 
 fn extract_date(
     context: &Context,
     config: Option<&ConfigEntry>,
     global_config: Option<&ConfigEntry>,
+    json_ld: Option<&JsonLd>,
+    time_zone: Option<&TimeZone>,
 ) -> Option<DateTime<Utc>> {
     // check site specific config
     if let Some(config) = config {
         for xpath_date in &config.xpath_date {
             if let Ok(date_string) = Util::extract_value(context, xpath_date) {
                 debug!("Article date: '{}'", date_string);
-                if let Ok(date) = DateTime::from_str(&date_string) {
+                if let Some(date) = parse_date(&date_string, time_zone) {
                     return Some(date);
-                } else {
-                    warn!("Parsing the date string '{}' failed", date_string);
                 }
             }
         }
     }
 
+    // check JSON-LD
+    if let Some(date_string) = json_ld.and_then(|json_ld| json_ld.date_published.as_deref()) {
+        debug!("Article date (JSON-LD): '{}'", date_string);
+        if let Some(date) = parse_date(date_string, time_zone) {
+            return Some(date);
+        }
+    }
+
     // check global config
     if let Some(global_config) = global_config {
         for xpath_date in &global_config.xpath_date {
             if let Ok(date_string) = Util::extract_value(context, xpath_date) {
                 debug!("Article date: '{}'", date_string);
-                if let Ok(date) = DateTime::from_str(&date_string) {
+                if let Some(date) = parse_date(&date_string, time_zone) {
                     return Some(date);
-                } else {
-                    warn!("Parsing the date string '{}' failed", date_string);
                 }
             }
         }
     }
 
-    None
+    // generic meta
+    get_meta_content(context, "article:published_time")
+        .or_else(|| Util::get_attribute(context, "//time[@datetime]", "datetime").ok())
+        .or_else(|| get_meta_content(context, "dc.date"))
+        .or_else(|| get_meta_content(context, "DC.date"))
+        .or_else(|| get_meta_content(context, "dcterms.date"))
+        .or_else(|| get_item_prop(context, "datePublished"))
+        .and_then(|date_string| {
+            debug!("Article date (meta): '{}'", date_string);
+            parse_date(&date_string, time_zone)
+        })
+}
+
+fn extract_date_modified(
+    context: &Context,
+    json_ld: Option<&JsonLd>,
+    time_zone: Option<&TimeZone>,
+) -> Option<DateTime<Utc>> {
+    // check JSON-LD
+    if let Some(date_string) = json_ld.and_then(|json_ld| json_ld.date_modified.as_deref()) {
+        debug!("Article modified date (JSON-LD): '{}'", date_string);
+        if let Some(date) = parse_date(date_string, time_zone) {
+            return Some(date);
+        }
+    }
+
+    // generic meta
+    get_meta_content(context, "article:modified_time")
+        .or_else(|| get_meta_content(context, "og:updated_time"))
+        .or_else(|| get_meta_content(context, "dcterms.modified"))
+        .or_else(|| get_item_prop(context, "dateModified"))
+        .and_then(|date_string| {
+            debug!("Article modified date (meta): '{}'", date_string);
+            parse_date(&date_string, time_zone)
+        })
+}
+
+fn parse_date(date_string: &str, time_zone: Option<&TimeZone>) -> Option<DateTime<Utc>> {
+    let date = date::parse_date(date_string, time_zone);
+    if date.is_none() {
+        warn!("Parsing the date string '{}' failed", date_string);
+    }
+    date
+}
+
+/// Value of a schema.org microdata property, e.g. `<meta itemprop="datePublished" content="...">`
+/// or `<time itemprop="datePublished" datetime="...">`
+fn get_item_prop(context: &Context, name: &str) -> Option<String> {
+    let node = Util::evaluate_xpath(context, &format!("//*[@itemprop='{name}']"), false)
+        .ok()?
+        .into_iter()
+        .next()?;
+
+    Some(item_prop_value(&node).trim().to_string())
+}
+
+fn item_prop_value(node: &Node) -> String {
+    node.get_attribute("content")
+        .or_else(|| node.get_attribute("datetime"))
+        .unwrap_or_else(|| node.get_content())
+}
+
+/// Content of the meta tag with the exact `name` or `property`
+fn get_meta_content(context: &Context, name: &str) -> Option<String> {
+    Util::get_attribute(
+        context,
+        &format!("//meta[@name='{name}' or @property='{name}'][@content]"),
+        "content",
+    )
+    .ok()
+    .filter(|content| !content.trim().is_empty())
+}
+
+fn get_meta_http_equiv(context: &Context, name: &str) -> Option<String> {
+    Util::get_attribute(
+        context,
+        &format!("//meta[translate(@http-equiv, 'ABCDEFGHIJKLMNOPQRSTUVWXYZ', 'abcdefghijklmnopqrstuvwxyz')='{name}']"),
+        "content",
+    )
+    .ok()
 }
 
 fn get_meta(context: &Context, name: &str) -> Option<String> {
@@ -1,11 +1,32 @@
 pub mod config;
+mod date;
+mod encoding;
 pub mod error;
+mod json_ld;
 mod metadata;
+mod options;
+mod page_provider;
+mod pass;
 mod readability;
+mod request_profile;
+mod site_extractor;
+mod site_test;
+mod trace;
 
-use self::config::{ConfigCollection, ConfigEntry};
+#[cfg(test)]
+mod tests;
+
+use self::config::{ConfigCollection, ConfigDiagnostic, ConfigEntry, PageLink, TestUrl};
+pub use self::encoding::decode_html;
 use self::error::FullTextParserError;
+pub use self::options::ExtractionOptions;
+pub use self::page_provider::PageProvider;
+pub use self::pass::{FnPass, Pass, PassContext, Passes};
 pub use self::readability::Readability;
+pub use self::request_profile::{Cookie, RequestProfile};
+pub use self::site_extractor::{NextData, NuxtState, SiteExtractor};
+pub use self::site_test::{snapshot_file_name, SiteTestReport, SiteTestResult, TestUrlResult};
+pub use self::trace::{collect_trace, TraceEvent};
 use crate::article::Article;
 use crate::constants;
 use crate::util::Util;
@@ -13,18 +34,77 @@ use crate::util::Util;
 use libxml::parser::Parser;
 use libxml::tree::{Document, Node, NodeType};
 use libxml::xpath::Context;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use url::Url;
 
+/// Used in place of `global.txt` for sites with `autodetect_on_failure: no`
+static EMPTY_CONFIG: Lazy<ConfigEntry> = Lazy::new(ConfigEntry::default);
+
+/// Config whose `body` rule matched in `extract_body`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BodyRule {
+    SiteConfig,
+    GlobalConfig,
+}
+
+/// Result of `parse_page` besides the content appended to the article
+struct ParsedPage {
+    /// Link of the first matching `next_page_link` rule
+    next_page_url: Option<Url>,
+    /// `None` if no `body` rule matched and Readability extracted the content
+    body_rule: Option<BodyRule>,
+}
+
+/// Extracts the content returned by a `SiteExtractor`, which gets wrapped in a `<div>`
+static SITE_EXTRACTOR_CONFIG: Lazy<ConfigEntry> = Lazy::new(|| ConfigEntry {
+    xpath_body: vec!["//body/div[1]".into()],
+    autodetect_on_failure: Some(false),
+    prune: Some(false),
+    ..Default::default()
+});
+
 pub struct FullTextParser {
     config_files: ConfigCollection,
+    options: ExtractionOptions,
+    site_extractors: Vec<Box<dyn SiteExtractor>>,
 }
 
 impl FullTextParser {
     pub fn new(config_path: Option<&Path>) -> Self {
         let config_files = ConfigCollection::parse(config_path);
-        Self { config_files }
+        Self {
+            config_files,
+            options: ExtractionOptions::default(),
+            site_extractors: site_extractor::builtin(),
+        }
+    }
+
+    /// Keep iframes, embeds and videos whose source matches `regex` instead of stripping them.
+    /// Defaults to YouTube, Vimeo, Dailymotion, PeerTube and a few others.
+    pub fn set_allowed_video_regex(&mut self, regex: Regex) {
+        self.options.allowed_video = regex;
+    }
+
+    /// Options used by all `parse_*` functions that don't take their own
+    pub fn set_options(&mut self, options: ExtractionOptions) {
+        self.options = options;
+    }
+
+    /// Try `extractor` before all other site extractors, the site config and Readability
+    pub fn register_site_extractor(&mut self, extractor: impl SiteExtractor + 'static) {
+        self.site_extractors.insert(0, Box::new(extractor));
+    }
+
+    /// Remove the site extractor called `name`, e.g. one of the built-in `next_data`
+    /// and `nuxt_state`. Returns `false` if there is none.
+    pub fn remove_site_extractor(&mut self, name: &str) -> bool {
+        let count = self.site_extractors.len();
+        self.site_extractors
+            .retain(|extractor| extractor.name() != name);
+        self.site_extractors.len() != count
     }
 
     pub fn parse_offline(
@@ -33,38 +113,250 @@ impl FullTextParser {
         config: Option<&ConfigEntry>,
         url: Option<Url>,
     ) -> Result<Article, FullTextParserError> {
-        let url = url.unwrap_or_else(|| url::Url::parse("http://fakehost/test/base/").unwrap());
+        self.parse_offline_with_options(pages, config, url, &self.options)
+    }
 
-        let config = if config.is_none() {
-            self.get_grabber_config(&url)
-        } else {
-            config
-        };
+    /// Like `parse_offline`, but with `options` instead of the ones of the parser
+    pub fn parse_offline_with_options(
+        &self,
+        pages: Vec<String>,
+        config: Option<&ConfigEntry>,
+        url: Option<Url>,
+        options: &ExtractionOptions,
+    ) -> Result<Article, FullTextParserError> {
+        let url = Self::article_url(url);
+        let config = self.resolve_config(config, &url);
+        if let Some(page_html) = pages.first() {
+            if let Some(article) = self.parse_site_extractor(page_html, config, &url, options)? {
+                return Ok(article);
+            }
+        }
 
-        let global_config = self
-            .config_files
-            .get("global.txt")
-            .ok_or(FullTextParserError::Config)?;
-
-        let mut article = Article {
-            title: None,
-            author: None,
-            url: url.clone(),
-            date: None,
-            thumbnail_url: None,
-            html: None,
-        };
+        self.parse_article(
+            config,
+            url,
+            options,
+            |article, root, config, global_config| {
+                for page_html in pages {
+                    self.parse_page(article, &page_html, root, config, global_config, options)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Like `parse_offline`, but takes the raw bytes of the pages and detects their
+    /// encoding. `content_type` is the `Content-Type` header the pages were served with.
+    pub fn parse_offline_bytes(
+        &self,
+        pages: Vec<Vec<u8>>,
+        content_type: Option<&str>,
+        config: Option<&ConfigEntry>,
+        url: Option<Url>,
+    ) -> Result<Article, FullTextParserError> {
+        self.parse_offline_bytes_with_options(pages, content_type, config, url, &self.options)
+    }
+
+    /// Like `parse_offline_bytes`, but with `options` instead of the ones of the parser
+    pub fn parse_offline_bytes_with_options(
+        &self,
+        pages: Vec<Vec<u8>>,
+        content_type: Option<&str>,
+        config: Option<&ConfigEntry>,
+        url: Option<Url>,
+        options: &ExtractionOptions,
+    ) -> Result<Article, FullTextParserError> {
+        let pages: Vec<_> = pages
+            .iter()
+            .map(|page| decode_html(page, content_type))
+            .collect();
+        let url = Self::article_url(url);
+        let config = self.resolve_config(config, &url);
+        if let Some((page_html, encoding)) = pages.first() {
+            if let Some(mut article) =
+                self.parse_site_extractor(page_html, config, &url, options)?
+            {
+                article.encoding = Some(encoding.name().into());
+                return Ok(article);
+            }
+        }
+
+        self.parse_article(
+            config,
+            url,
+            options,
+            |article, root, config, global_config| {
+                for (page_html, encoding) in pages {
+                    if article.encoding.is_none() {
+                        article.encoding = Some(encoding.name().into());
+                    }
+                    self.parse_page(article, &page_html, root, config, global_config, options)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Parse the first page of an article and follow the rules of the site config
+    /// to get the complete article.
+    ///
+    /// If a `single_page_link` rule matches, the single page view is requested from
+    /// `provider` and extracted instead. Otherwise `next_page_link` rules are followed:
+    /// the HTML of every following page is requested from `provider` and appended to the
+    /// article until no further link is found, a page was already visited or
+    /// `constants::MAX_PAGES` is reached.
+    pub fn parse_with_provider<P: PageProvider>(
+        &self,
+        html: &str,
+        config: Option<&ConfigEntry>,
+        url: Option<Url>,
+        provider: &mut P,
+    ) -> Result<Article, FullTextParserError> {
+        let url = Self::article_url(url);
+        let config = self.resolve_config(config, &url);
+        if let Some(article) = self.parse_site_extractor(html, config, &url, &self.options)? {
+            return Ok(article);
+        }
+
+        self.parse_article(
+            config,
+            url,
+            &self.options,
+            |article, root, config, global_config| {
+                let single_page_html =
+                    Self::find_single_page_url(html, config, global_config, &article.url)?
+                        .filter(|single_page_url| single_page_url != &article.url)
+                        .and_then(|single_page_url| {
+                            let single_page_html = provider.get_page(&single_page_url);
+                            if single_page_html.is_none() {
+                                log::warn!("Failed to get single page '{single_page_url}'");
+                            }
+                            single_page_html
+                        });
+
+                if let Some(single_page_html) = single_page_html {
+                    self.parse_page(
+                        article,
+                        &single_page_html,
+                        root,
+                        config,
+                        global_config,
+                        &self.options,
+                    )?;
+                    return Ok(());
+                }
+
+                let mut visited_pages = HashSet::from([article.url.clone()]);
+                let mut next_page_url = self
+                    .parse_page(article, html, root, config, global_config, &self.options)?
+                    .next_page_url;
+
+                while let Some(page_url) = next_page_url.take() {
+                    if visited_pages.len() >= constants::MAX_PAGES {
+                        log::warn!(
+                            "Reached maximum of {} pages, not following '{page_url}'",
+                            constants::MAX_PAGES
+                        );
+                        break;
+                    }
+
+                    if !visited_pages.insert(page_url.clone()) {
+                        log::warn!("Page '{page_url}' was already parsed, stopping pagination");
+                        break;
+                    }
+
+                    let Some(page_html) = provider.get_page(&page_url) else {
+                        log::warn!("Failed to get next page '{page_url}'");
+                        break;
+                    };
+
+                    next_page_url = match self.parse_page(
+                        article,
+                        &page_html,
+                        root,
+                        config,
+                        global_config,
+                        &self.options,
+                    ) {
+                        Ok(page) => page.next_page_url,
+                        Err(error) => {
+                            log::warn!("Failed to parse next page '{page_url}': {error}");
+                            break;
+                        }
+                    };
+                }
 
-        libxml::tree::node::set_node_rc_guard(10);
+                Ok(())
+            },
+        )
+    }
+
+    /// Check the first page of an article for a link to a view containing the whole
+    /// article on a single page (e.g. the print view) as specified by the `single_page_link`
+    /// rules of the site config. The returned URL is resolved against `url`.
+    ///
+    /// Fetch the returned URL and hand its HTML to `parse_offline` to extract the
+    /// complete article. `parse_with_provider` does both steps on its own.
+    pub fn single_page_url(
+        &self,
+        html: &str,
+        config: Option<&ConfigEntry>,
+        url: &Url,
+    ) -> Result<Option<Url>, FullTextParserError> {
+        let config = self.resolve_config(config, url);
+        let global_config = self.get_global_config(config)?;
+
+        Self::find_single_page_url(html, config, global_config, url)
+    }
+
+    fn find_single_page_url(
+        html: &str,
+        config: Option<&ConfigEntry>,
+        global_config: &ConfigEntry,
+        base_url: &Url,
+    ) -> Result<Option<Url>, FullTextParserError> {
+        let rules = config
+            .map(|config| config.single_page_link.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .chain(&global_config.single_page_link);
+        if rules.clone().next().is_none() {
+            return Ok(None);
+        }
+
+        let document = Self::parse_html(html, config, global_config)?;
+        let xpath_ctx = Self::get_xpath_ctx(&document)?;
+        let base_url = Self::base_url(&xpath_ctx, base_url);
+        Ok(Self::find_page_link(&xpath_ctx, rules, &base_url))
+    }
+
+    /// Build the article of `url` from the pages `parse_pages` adds to its body.
+    /// `config` has to be resolved by the caller already, see `resolve_config`.
+    fn parse_article<F>(
+        &self,
+        config: Option<&ConfigEntry>,
+        url: Url,
+        options: &ExtractionOptions,
+        parse_pages: F,
+    ) -> Result<Article, FullTextParserError>
+    where
+        F: FnOnce(
+            &mut Article,
+            &mut Node,
+            Option<&ConfigEntry>,
+            &ConfigEntry,
+        ) -> Result<(), FullTextParserError>,
+    {
+        let global_config = self.get_global_config(config)?;
+
+        let mut article = Article::new(url);
 
         let mut document = Document::new().map_err(|()| FullTextParserError::Xml)?;
         let mut root =
             Node::new("article", None, &document).map_err(|()| FullTextParserError::Xml)?;
         document.set_root_element(&root);
 
-        for page_html in pages {
-            self.parse_page(&mut article, &page_html, &mut root, config, global_config)?;
-        }
+        parse_pages(&mut article, &mut root, config, global_config)?;
 
         let context = Context::new(&document).map_err(|()| {
             log::error!("Failed to create xpath context for extracted article");
@@ -76,12 +368,78 @@ impl FullTextParser {
             return Err(error);
         }
 
-        Self::post_process_document(&document)?;
+        Self::post_process_document(&document, config, global_config, &article.url, options)?;
+        metadata::extract_from_body(&root, &mut article);
         article.html = Some(Util::serialize_node(&document, &root));
 
         Ok(article)
     }
 
+    /// Let the first matching site extractor that doesn't decline extract the article
+    /// from `html`. Its content is cleaned like a page body and the metadata it didn't
+    /// provide is taken from the page.
+    ///
+    /// Generic extractors are skipped if there is a site config for the page, `config`
+    /// is already resolved by the caller. `None` if no extractor returned an article or
+    /// its content was empty after cleaning.
+    fn parse_site_extractor(
+        &self,
+        html: &str,
+        config: Option<&ConfigEntry>,
+        url: &Url,
+        options: &ExtractionOptions,
+    ) -> Result<Option<Article>, FullTextParserError> {
+        let extracted = self
+            .site_extractors
+            .iter()
+            .filter(|extractor| extractor.matches(url))
+            .filter(|extractor| config.is_none() || !extractor.generic())
+            .find_map(|extractor| {
+                let article = extractor.extract(html, url)?;
+                if article.html.is_none() {
+                    log::warn!("Site extractor '{}' returned no content", extractor.name());
+                    return None;
+                }
+                log::info!("Article extracted by site extractor '{}'", extractor.name());
+                trace::record(|| TraceEvent::SiteExtractor {
+                    name: extractor.name().into(),
+                });
+                Some(article)
+            });
+        let Some(mut extracted) = extracted else {
+            return Ok(None);
+        };
+        let content = format!("<div>{}</div>", extracted.html.take().unwrap_or_default());
+
+        let global_config = self.get_global_config(config)?;
+        let document = Self::parse_html(html, config, global_config)?;
+        let xpath_ctx = Self::get_xpath_ctx(&document)?;
+        metadata::extract(&xpath_ctx, config, Some(global_config), &mut extracted);
+
+        let article = self.parse_article(
+            Some(&SITE_EXTRACTOR_CONFIG),
+            url.clone(),
+            options,
+            |article, root, config, global_config| {
+                *article = extracted;
+                self.parse_page(article, &content, root, config, global_config, options)?;
+                if Util::get_inner_text(root, false).trim().is_empty() {
+                    return Err(FullTextParserError::Scrape);
+                }
+                Ok(())
+            },
+        );
+        match article {
+            Err(FullTextParserError::AutodetectDisabled | FullTextParserError::Scrape) => {
+                log::warn!("Content of the site extractor is empty after cleaning");
+                Ok(None)
+            }
+            article => article.map(Some),
+        }
+    }
+
+    /// Parse a single page into `root` and return the URL of the next page
+    /// if a `next_page_link` rule matched and which `body` rule matched
     fn parse_page(
         &self,
         article: &mut Article,
@@ -89,7 +447,8 @@ impl FullTextParser {
         root: &mut Node,
         config: Option<&ConfigEntry>,
         global_config: &ConfigEntry,
-    ) -> Result<(), FullTextParserError> {
+        options: &ExtractionOptions,
+    ) -> Result<ParsedPage, FullTextParserError> {
         let document = Self::parse_html(html, config, global_config)?;
         let xpath_ctx = Self::get_xpath_ctx(&document)?;
 
@@ -98,6 +457,16 @@ impl FullTextParser {
         if article.thumbnail_url.is_none() {
             article.thumbnail_url = Self::check_for_thumbnail(&xpath_ctx);
         }
+
+        // the link has to be evaluated before the document gets stripped
+        let rules = config
+            .map(|config| config.next_page_link.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .chain(&global_config.next_page_link);
+        let base_url = Self::base_url(&xpath_ctx, &article.url);
+        let next_page_url = Self::find_page_link(&xpath_ctx, rules, &base_url);
+
         Self::prep_content(
             &xpath_ctx,
             config,
@@ -105,18 +474,102 @@ impl FullTextParser {
             &article.url,
             &document,
             article.title.as_deref(),
+            options,
         );
-        let found_body = Self::extract_body(&xpath_ctx, root, config, global_config)?;
+        let body_rule = match document.get_root_element() {
+            Some(page_root) => {
+                let ctx = PassContext {
+                    document: &document,
+                    context: &xpath_ctx,
+                    root: page_root,
+                    config,
+                    global_config,
+                    url: &base_url,
+                    title: article.title.as_deref(),
+                    options,
+                };
+                Self::extract_body(&ctx, root)?
+            }
+            None => None,
+        };
 
-        if !found_body {
-            if let Err(error) = Readability::extract_body(document, root, article.title.as_deref())
-            {
+        if body_rule.is_none() {
+            if config.is_some_and(|config| !config.autodetect_on_failure()) {
+                log::error!("Site config found no body and autodetect_on_failure is disabled");
+                return Err(FullTextParserError::AutodetectDisabled);
+            }
+
+            trace::record(|| TraceEvent::ReadabilityFallback);
+            if let Err(error) = Readability::extract_body(document, root, article, options) {
                 log::error!("Both ftr and readability failed to find content: {error}");
                 return Err(error);
             }
         }
 
-        Ok(())
+        Ok(ParsedPage {
+            next_page_url,
+            body_rule,
+        })
+    }
+
+    /// Return the link of the first `single_page_link` or `next_page_link` rule
+    /// whose `if_page_contains` condition matches the document and that yields a link
+    fn find_page_link<'a>(
+        context: &Context,
+        rules: impl Iterator<Item = &'a PageLink>,
+        base_url: &Url,
+    ) -> Option<Url> {
+        rules
+            .filter(|rule| match &rule.condition {
+                Some(condition) => Self::page_contains(context, condition),
+                None => true,
+            })
+            .find_map(|rule| {
+                let url = Self::evaluate_page_link(context, &rule.xpath, base_url)?;
+                trace::record(|| TraceEvent::PageLink {
+                    rule: rule.xpath.clone(),
+                    url: url.clone(),
+                });
+                Some(url)
+            })
+    }
+
+    fn page_contains(context: &Context, xpath: &str) -> bool {
+        match context.evaluate(xpath) {
+            Ok(result) => !result.get_nodes_as_vec().is_empty(),
+            Err(()) => {
+                log::warn!("Failed to evaluate if_page_contains xpath '{xpath}'");
+                false
+            }
+        }
+    }
+
+    /// Evaluate a page link xpath and resolve the result against `base_url`.
+    /// Supports xpaths returning elements (`href` or text content), attributes
+    /// and plain strings (e.g. `concat(...)`).
+    fn evaluate_page_link(context: &Context, xpath: &str, base_url: &Url) -> Option<Url> {
+        let result = context.evaluate(xpath).ok()?;
+        let link = match result.get_nodes_as_vec().first() {
+            Some(node) if node.get_type() == Some(NodeType::ElementNode) => node
+                .get_attribute("href")
+                .unwrap_or_else(|| node.get_content()),
+            Some(node) => node.get_content(),
+            None => result.to_string(),
+        };
+
+        let link = link.trim();
+        if link.is_empty() {
+            log::debug!("Page link xpath '{xpath}' yielded no results");
+            return None;
+        }
+
+        match base_url.join(link) {
+            Ok(url) => Some(url),
+            Err(error) => {
+                log::warn!("Failed to resolve page link '{link}': {error}");
+                None
+            }
+        }
     }
 
     pub(crate) fn parse_html(
@@ -149,7 +602,7 @@ impl FullTextParser {
     /// See:
     /// - <https://github.com/KWARC/rust-libxml/issues/111>
     /// - <https://github.com/Orange-OpenSource/hurl/issues/1535>
-    ///     These two functions should be removed when the issue is fixed in libxml crate.
+    ///   These two functions should be removed when the issue is fixed in libxml crate.
     fn try_usize_to_i32(value: usize) -> Result<i32, libxml::parser::XmlParseError> {
         if cfg!(target_pointer_width = "16") || (value < i32::MAX as usize) {
             // Cannot safely use our value comparison, but the conversion if always safe.
@@ -233,19 +686,190 @@ impl FullTextParser {
         }
     }
 
+    /// Problems found in the user configs while loading them
+    pub fn config_diagnostics(&self) -> &[ConfigDiagnostic] {
+        self.config_files.diagnostics()
+    }
+
+    /// Lint all site configs embedded in the crate
+    pub fn lint_embedded_configs() -> Vec<ConfigDiagnostic> {
+        ConfigCollection::lint_embedded()
+    }
+
+    /// HTTP headers, user agent and cookies that should be used to request `url`
+    /// according to the matching site config and `global.txt`
+    pub fn request_profile(&self, url: &Url) -> RequestProfile {
+        let config = self.get_grabber_config(url);
+        RequestProfile::new(config, self.get_global_config(config).ok())
+    }
+
+    /// Run the `test_url` entries of the site configs against HTML snapshots saved
+    /// in `snapshot_dir`. Snapshots are looked up by `snapshot_file_name`. `configs`
+    /// limits the check to the site configs with these file names, e.g. `example.com.txt`,
+    /// `None` checks all user and embedded configs.
+    ///
+    /// Every snapshot is extracted like `parse_offline` does with the config it belongs to,
+    /// but without site extractors. The report lists whether a `body` rule of the config
+    /// matched and which `test_contains` strings are missing. Test URLs without a snapshot
+    /// are listed as missing, sites without any snapshot are listed separately.
+    pub fn verify_site_configs(
+        &self,
+        snapshot_dir: &Path,
+        configs: Option<&[&str]>,
+    ) -> SiteTestReport {
+        let mut report = SiteTestReport::default();
+
+        for (config_name, config) in self.config_files.iter() {
+            if configs.is_some_and(|configs| !configs.contains(&config_name)) {
+                continue;
+            }
+
+            let mut site = SiteTestResult {
+                config: config_name.into(),
+                tests: Vec::new(),
+                missing_snapshots: Vec::new(),
+            };
+
+            for test_url in &config.test_url {
+                let snapshot_path = snapshot_dir.join(snapshot_file_name(&test_url.url));
+                match std::fs::read_to_string(&snapshot_path) {
+                    Ok(html) => site
+                        .tests
+                        .push(self.verify_test_url(test_url, html, config)),
+                    Err(_) => site.missing_snapshots.push(test_url.url.clone()),
+                }
+            }
+
+            if !site.tests.is_empty() {
+                report.sites.push(site);
+            } else if !site.missing_snapshots.is_empty() {
+                report.sites_without_snapshots.push(site.config);
+            }
+        }
+
+        report.sites.sort_by(|a, b| a.config.cmp(&b.config));
+        report.sites_without_snapshots.sort();
+        report
+    }
+
+    fn verify_test_url(
+        &self,
+        test_url: &TestUrl,
+        html: String,
+        config: &ConfigEntry,
+    ) -> TestUrlResult {
+        let mut result = TestUrlResult {
+            url: test_url.url.clone(),
+            body_found: false,
+            missing_strings: Vec::new(),
+            error: None,
+        };
+
+        let url = match Url::parse(&test_url.url) {
+            Ok(url) => url,
+            Err(error) => {
+                result.error = Some(format!("invalid test_url: {error}"));
+                return result;
+            }
+        };
+
+        let mut body_rule = None;
+        let article = self.parse_article(
+            Some(config),
+            url,
+            &self.options,
+            |article, root, config, global_config| {
+                let page =
+                    self.parse_page(article, &html, root, config, global_config, &self.options)?;
+                body_rule = page.body_rule;
+                Ok(())
+            },
+        );
+
+        match article {
+            Ok(article) => {
+                let html = article.html.unwrap_or_default();
+                result.body_found = body_rule == Some(BodyRule::SiteConfig);
+                result.missing_strings = test_url
+                    .contains
+                    .iter()
+                    .filter(|string| !html.contains(string.as_str()))
+                    .cloned()
+                    .collect();
+            }
+            Err(error) => result.error = Some(error.to_string()),
+        }
+
+        result
+    }
+
+    /// Url of the article, a placeholder if the caller doesn't know it
+    fn article_url(url: Option<Url>) -> Url {
+        url.unwrap_or_else(|| url::Url::parse("http://fakehost/test/base/").unwrap())
+    }
+
+    /// `config` if the caller passed one, the site config of `url` otherwise
+    fn resolve_config<'a>(
+        &'a self,
+        config: Option<&'a ConfigEntry>,
+        url: &Url,
+    ) -> Option<&'a ConfigEntry> {
+        config.or_else(|| self.get_grabber_config(url))
+    }
+
+    /// Look up the site config for `url` like Full-Text RSS does: the exact host
+    /// (`www.` stripped) first, then wildcard configs of the parent domains, e.g.
+    /// `.dreamwidth.org.txt` for `someone.dreamwidth.org`
     fn get_grabber_config(&self, url: &url::Url) -> Option<&ConfigEntry> {
-        let conf = Self::get_host_name(url)
-            .ok()
-            .map(|url| url + ".txt")
-            .and_then(|name| self.config_files.get(&name));
+        let host = Self::get_host_name(url).ok();
+        let conf = host.as_deref().and_then(|host| {
+            Self::config_file_names(host).into_iter().find_map(|name| {
+                let conf = self.config_files.get(&name)?;
+                trace::record(|| TraceEvent::SiteConfig { file: name });
+                Some(conf)
+            })
+        });
 
         if conf.is_none() {
             log::warn!("No config found for url '{}'", url);
+            trace::record(|| TraceEvent::NoSiteConfig {
+                host: host.unwrap_or_default(),
+            });
         }
 
         conf
     }
 
+    fn config_file_names(host: &str) -> Vec<String> {
+        let mut names = vec![format!("{host}.txt")];
+
+        let mut parent = host;
+        while let Some((_, domain)) = parent.split_once('.') {
+            // don't match whole top level domains like `.com.txt`
+            if !domain.contains('.') {
+                break;
+            }
+            names.push(format!(".{domain}.txt"));
+            parent = domain;
+        }
+
+        names
+    }
+
+    /// `global.txt` or an empty config if the site config disables `autodetect_on_failure`
+    fn get_global_config(
+        &self,
+        config: Option<&ConfigEntry>,
+    ) -> Result<&ConfigEntry, FullTextParserError> {
+        if config.is_some_and(|config| !config.autodetect_on_failure()) {
+            return Ok(&EMPTY_CONFIG);
+        }
+
+        self.config_files
+            .get("global.txt")
+            .ok_or(FullTextParserError::Config)
+    }
+
     pub fn thumbnail_from_html(html: &str) -> Option<String> {
         if let Ok(doc) = Self::parse_html_string_patched(html) {
             if let Ok(ctx) = Self::get_xpath_ctx(&doc) {
@@ -341,7 +965,11 @@ impl FullTextParser {
         None
     }
 
-    fn fix_lazy_images(context: &Context, doc: &Document) -> Result<(), FullTextParserError> {
+    fn fix_lazy_images(
+        context: &Context,
+        doc: &Document,
+        lazy_load_attr: Option<&str>,
+    ) -> Result<(), FullTextParserError> {
         let mut img_nodes = Util::evaluate_xpath(context, "//img", false)?;
         let pic_nodes = Util::evaluate_xpath(context, "//picture", false)?;
         let fig_nodes = Util::evaluate_xpath(context, "//figure", false)?;
@@ -352,6 +980,17 @@ impl FullTextParser {
         for mut node in img_nodes {
             let tag_name = node.get_name().to_uppercase();
 
+            // the site config knows exactly where the real image is located
+            if tag_name == "IMG" {
+                if let Some(lazy_src) = lazy_load_attr
+                    .and_then(|attr| node.get_attribute(attr))
+                    .filter(|src| !src.trim().is_empty())
+                {
+                    _ = node.set_attribute("src", lazy_src.trim());
+                    continue;
+                }
+            }
+
             // In some sites (e.g. Kotaku), they put 1px square image as base64 data uri in the src attribute.
             // So, here we check if the data uri is too short, just might as well remove it.
             if let Some(src) = node.get_attribute("src") {
@@ -434,34 +1073,41 @@ impl FullTextParser {
         Ok(())
     }
 
-    fn fix_iframe_size(context: &Context, site_name: &str) -> Result<(), FullTextParserError> {
-        let xpath = &format!("//iframe[contains(@src, '{}')]", site_name);
+    /// Keep and size the iframes, embeds and videos whose attributes match
+    /// `allowed_video` and strip all other iframes and embeds if `strip_embeds` is set
+    fn fix_video_embeds(
+        context: &Context,
+        options: &ExtractionOptions,
+    ) -> Result<(), FullTextParserError> {
+        let xpath = "//iframe | //object | //embed | //video";
         let node_vec = Util::evaluate_xpath(context, xpath, false)?;
         for mut node in node_vec {
             if node.is_null() {
                 continue;
             }
 
-            let video_wrapper = node
-                .get_parent()
-                .and_then(|mut parent| parent.new_child(None, "div").ok());
-            if let Some(mut video_wrapper) = video_wrapper {
-                let success = video_wrapper
-                    .set_property("class", "videoWrapper")
-                    .ok()
-                    .and_then(|()| node.set_property("width", "480").ok())
-                    .and_then(|()| node.set_property("height", "360").ok())
-                    .and_then(|()| node.set_property("aspect-ratio", "auto").ok())
-                    .ok_or_else(|| {
-                        node.unlink();
-                        video_wrapper.add_child(&mut node)
-                    })
-                    .is_err();
-                if !success {
-                    log::debug!("Failed to add iframe as child of video wrapper <div>");
+            let sources: Vec<String> = node
+                .findnodes("./source/@src")
+                .unwrap_or_default()
+                .iter()
+                .map(Node::get_content)
+                .collect();
+            let is_allowed = node
+                .get_attributes()
+                .values()
+                .chain(&sources)
+                .any(|value| options.allowed_video.is_match(value));
+
+            if is_allowed {
+                // only size embeds the author left unsized, keep any existing dimensions
+                if !node.has_attribute("width") && !node.has_attribute("height") {
+                    _ = node.set_property("width", "480");
+                    _ = node.set_property("height", "360");
+                    _ = node.set_property("aspect-ratio", "auto");
                 }
-            } else {
-                log::warn!("Failed to get parent of iframe");
+                _ = node.set_property(constants::ALLOWED_VIDEO_ATTR, "true");
+            } else if options.strip_embeds && !node.get_name().eq_ignore_ascii_case("video") {
+                node.unlink();
             }
         }
         Ok(())
@@ -510,41 +1156,12 @@ impl FullTextParser {
                     .map(|err| err == url::ParseError::RelativeUrlWithoutBase)
                     .unwrap_or(false);
                 let is_javascript = trimmed_url.contains("javascript:");
+                let is_link = node.get_name().to_uppercase() == "A";
 
-                if !is_hash_url && node.get_name().to_uppercase() == "A" {
+                if !is_hash_url && is_link {
                     _ = node.set_attribute("target", "_blank");
                 }
 
-                if let Some(srcset) = node.get_attribute("srcset") {
-                    let res = constants::SRC_SET_URL
-                        .captures_iter(&srcset)
-                        .map(|cap| {
-                            let cap0 = cap.get(0).map_or("", |m| m.as_str());
-                            let cap1 = cap.get(1).map_or("", |m| m.as_str());
-                            let cap2 = cap.get(2).map_or("", |m| m.as_str());
-                            let cap3 = cap.get(3).map_or("", |m| m.as_str());
-
-                            let is_relative_url = url::Url::parse(cap1)
-                                .err()
-                                .map(|err| err == url::ParseError::RelativeUrlWithoutBase)
-                                .unwrap_or(false);
-
-                            if is_relative_url {
-                                let completed_url = article_url
-                                    .join(cap1)
-                                    .map(|u| u.as_str().to_owned())
-                                    .unwrap_or_default();
-                                format!("{completed_url}{cap2}{cap3}")
-                            } else {
-                                cap0.to_string()
-                            }
-                        })
-                        .collect::<Vec<String>>()
-                        .join(" ");
-
-                    _ = node.set_attribute("srcset", res.as_str());
-                }
-
                 if is_hash_url {
                     _ = node.set_attribute(attribute, trimmed_url);
                 } else if is_relative_url {
@@ -553,6 +1170,8 @@ impl FullTextParser {
                         Err(_) => continue,
                     };
                     _ = node.set_attribute(attribute, completed_url.as_str());
+                } else if is_javascript && !is_link {
+                    _ = node.remove_attribute(attribute);
                 } else if is_javascript {
                     // if the link only contains simple text content, it can be converted to a text node
                     let mut child_nodes = node.get_child_nodes();
@@ -589,147 +1208,99 @@ impl FullTextParser {
         Ok(())
     }
 
-    fn fix_urls(context: &Context, url: &Url, document: &Document) {
-        _ = Self::repair_urls(context, "//img", "src", url, document);
-        _ = Self::repair_urls(context, "//a", "src", url, document);
-        _ = Self::repair_urls(context, "//a", "href", url, document);
-        _ = Self::repair_urls(context, "//object", "data", url, document);
-        _ = Self::repair_urls(context, "//iframe", "src", url, document);
+    /// Base URL of the document: the `<base href>` resolved against `url`, or `url` itself
+    pub(crate) fn base_url(context: &Context, url: &Url) -> Url {
+        Util::get_attribute(context, "//base[@href]", "href")
+            .ok()
+            .and_then(|href| url.join(href.trim()).ok())
+            .unwrap_or_else(|| url.clone())
     }
 
-    pub(crate) fn prep_content(
+    fn repair_srcset(
         context: &Context,
-        config: Option<&ConfigEntry>,
-        global_config: &ConfigEntry,
-        url: &Url,
-        document: &Document,
-        title: Option<&str>,
-    ) {
-        // replace H1 with H2 as H1 should be only title that is displayed separately
-        if let Ok(h1_nodes) = Util::evaluate_xpath(context, "//h1", false) {
-            for mut h1_node in h1_nodes {
-                _ = h1_node.set_name("h2");
-            }
-        }
-
-        if let Ok(h2_nodes) = Util::evaluate_xpath(context, "//h2", false) {
-            for mut h2_node in h2_nodes {
-                if h2_node.is_null() {
-                    continue;
-                }
-
-                if Util::header_duplicates_title(&h2_node, title) {
-                    h2_node.unlink();
-                }
-            }
-        }
-
-        // rename all font nodes to span
-        if let Ok(font_nodes) = Util::evaluate_xpath(context, "//font", false) {
-            for mut font_node in font_nodes {
-                _ = font_node.set_name("span");
-            }
-        }
-
-        _ = Util::mark_data_tables(context);
-
-        // strip specified xpath
-        if let Some(config) = config {
-            for xpath_strip in &config.xpath_strip {
-                _ = Util::strip_node(context, xpath_strip);
-            }
-        }
-
-        for xpath_strip in &global_config.xpath_strip {
-            _ = Util::strip_node(context, xpath_strip);
-        }
-
-        // strip everything with specified 'id' or 'class'
-        if let Some(config) = config {
-            for xpaht_strip_class in &config.strip_id_or_class {
-                _ = Util::strip_id_or_class(context, xpaht_strip_class);
-            }
-        }
-
-        for xpaht_strip_class in &global_config.strip_id_or_class {
-            _ = Util::strip_id_or_class(context, xpaht_strip_class);
-        }
+        xpath: &str,
+        article_url: &url::Url,
+    ) -> Result<(), FullTextParserError> {
+        let node_vec = Util::evaluate_xpath(context, xpath, false)?;
+        for mut node in node_vec {
+            if let Some(srcset) = node.get_attribute("srcset") {
+                let res = constants::SRC_SET_URL
+                    .captures_iter(&srcset)
+                    .map(|cap| {
+                        let cap0 = cap.get(0).map_or("", |m| m.as_str());
+                        let cap1 = cap.get(1).map_or("", |m| m.as_str());
+                        let cap2 = cap.get(2).map_or("", |m| m.as_str());
+                        let cap3 = cap.get(3).map_or("", |m| m.as_str());
+
+                        let is_relative_url = url::Url::parse(cap1)
+                            .err()
+                            .map(|err| err == url::ParseError::RelativeUrlWithoutBase)
+                            .unwrap_or(false);
+
+                        if is_relative_url {
+                            let completed_url = article_url
+                                .join(cap1)
+                                .map(|u| u.as_str().to_owned())
+                                .unwrap_or_default();
+                            format!("{completed_url}{cap2}{cap3}")
+                        } else {
+                            cap0.to_string()
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ");
 
-        // strip any <img> element where @src attribute contains this substring
-        if let Some(config) = config {
-            for xpath_strip_img_src in &config.strip_image_src {
-                _ = Util::strip_node(
-                    context,
-                    &format!("//img[contains(@src,'{}')]", xpath_strip_img_src),
-                );
+                _ = node.set_attribute("srcset", res.as_str());
             }
         }
+        Ok(())
+    }
 
-        for xpath_strip_img_src in &global_config.strip_image_src {
-            _ = Util::strip_node(
+    fn fix_urls(context: &Context, url: &Url, document: &Document) {
+        for (tag, attribute) in constants::URL_ATTRIBUTES {
+            _ = Self::repair_urls(
                 context,
-                &format!("//img[contains(@src,'{}')]", xpath_strip_img_src),
+                &format!("//{tag}[@{attribute}]"),
+                attribute,
+                url,
+                document,
             );
         }
 
-        _ = Self::unwrap_noscript_images(context);
-        _ = Util::strip_node(context, "//noscript");
+        for tag in constants::SRCSET_ELEMS {
+            _ = Self::repair_srcset(context, &format!("//{tag}[@srcset]"), url);
+        }
+    }
 
-        _ = Self::fix_lazy_images(context, document);
-        _ = Self::fix_iframe_size(context, "youtube.com");
-        _ = Self::remove_attribute(context, Some("a"), "onclick");
-        _ = Self::remove_attribute(context, Some("img"), "decoding");
-        _ = Self::remove_attribute(context, Some("img"), "loading");
+    pub(crate) fn prep_content(
+        context: &Context,
+        config: Option<&ConfigEntry>,
+        global_config: &ConfigEntry,
+        url: &Url,
+        document: &Document,
+        title: Option<&str>,
+        options: &ExtractionOptions,
+    ) {
+        let Some(root) = document.get_root_element() else {
+            return;
+        };
 
-        // strip elements using Readability.com and Instapaper.com ignore class names
-        // .entry-unrelated and .instapaper_ignore
-        // See http://blog.instapaper.com/post/730281947
-        _ = Util::strip_node(
+        // the <base> element may get stripped along the way
+        let base_url = Self::base_url(context, url);
+        let ctx = PassContext {
+            document,
             context,
-            "//*[contains(@class,' entry-unrelated ') or contains(@class,' instapaper_ignore ')]",
-        );
-
-        // strip elements that contain style="display: none;"
-        _ = Util::strip_node(context, "//*[contains(@style,'display:none')]");
-        _ = Util::strip_node(context, "//*[contains(@style,'display: none')]");
-        _ = Self::remove_attribute(context, None, "style");
-
-        // strip all input elements
-        _ = Util::strip_node(context, "//form");
-        _ = Util::strip_node(context, "//input");
-        _ = Util::strip_node(context, "//textarea");
-        _ = Util::strip_node(context, "//select");
-        _ = Util::strip_node(context, "//button");
-
-        // strip all comments
-        _ = Util::strip_node(context, "//comment()");
-
-        // strip all scripts
-        _ = Util::strip_node(context, "//script");
-
-        // strip all styles
-        _ = Util::strip_node(context, "//style");
-
-        // strip all empty url-tags <a/>
-        _ = Util::strip_node(context, "//a[not(node())]");
-
-        // strip all external css and fonts
-        _ = Util::strip_node(context, "//*[@type='text/css']");
-
-        // other junk
-        _ = Util::strip_node(context, "//iframe");
-        _ = Util::strip_node(context, "//object");
-        _ = Util::strip_node(context, "//embed");
-        _ = Util::strip_node(context, "//footer");
-        _ = Util::strip_node(context, "//link");
-        _ = Util::strip_node(context, "//aside");
-
-        if let Some(root) = document.get_root_element() {
-            Util::replace_brs(&root, document);
-            Util::replace_emoji_images(&root, document);
+            root,
+            config,
+            global_config,
+            url: &base_url,
+            title,
+            options,
+        };
+        // preparing the page is best effort, every pass has run even if some failed
+        if let Err(error) = options.prepare_passes.run(&ctx) {
+            log::warn!("Preparing the page was incomplete: {error}");
         }
-
-        Self::fix_urls(context, url, document);
     }
 
     /**
@@ -837,51 +1408,63 @@ impl FullTextParser {
         Ok(())
     }
 
+    /// Extract the nodes matching the `body` rules of the site config into `root`, or
+    /// the ones of `global.txt` if none of them matched
     fn extract_body(
-        context: &Context,
+        ctx: &PassContext,
         root: &mut Node,
-        config: Option<&ConfigEntry>,
-        global_config: &ConfigEntry,
-    ) -> Result<bool, FullTextParserError> {
+    ) -> Result<Option<BodyRule>, FullTextParserError> {
         let mut found_something = false;
 
-        if let Some(config) = config {
+        if let Some(config) = ctx.config {
             for xpath_body in &config.xpath_body {
-                if Self::extract_body_single(context, root, xpath_body)? {
+                if Self::extract_body_single(ctx, root, xpath_body)? {
                     found_something = true;
                 }
             }
         }
 
-        if !found_something {
-            for xpath_body in &global_config.xpath_body {
-                if Self::extract_body_single(context, root, xpath_body)? {
-                    found_something = true;
-                }
+        if found_something {
+            return Ok(Some(BodyRule::SiteConfig));
+        }
+
+        for xpath_body in &ctx.global_config.xpath_body {
+            if Self::extract_body_single(ctx, root, xpath_body)? {
+                found_something = true;
             }
         }
 
-        Ok(found_something)
+        Ok(found_something.then_some(BodyRule::GlobalConfig))
     }
 
     fn extract_body_single(
-        context: &Context,
+        ctx: &PassContext,
         root: &mut Node,
         xpath: &str,
     ) -> Result<bool, FullTextParserError> {
         let mut found_something = false;
         {
-            let node_vec = Util::evaluate_xpath(context, xpath, false)?;
+            let node_vec = Util::evaluate_xpath(ctx.context, xpath, false)?;
+            trace::record(|| TraceEvent::Body {
+                rule: xpath.into(),
+                nodes: node_vec.len(),
+            });
             for mut node in node_vec {
                 if node.is_null() {
                     continue;
                 }
 
-                if node.get_property("style").is_some() && node.remove_property("style").is_err() {
+                if ctx.options.strip_styles
+                    && node.get_property("style").is_some()
+                    && node.remove_property("style").is_err()
+                {
                     return Err(FullTextParserError::Xml);
                 }
 
-                Self::post_process_page(&mut node)?;
+                Self::post_process_page(&PassContext {
+                    root: node.clone(),
+                    ..*ctx
+                })?;
 
                 node.unlink();
                 if root.add_child(&mut node).is_ok() {
@@ -914,32 +1497,34 @@ impl FullTextParser {
         Ok(())
     }
 
-    pub(crate) fn post_process_document(document: &Document) -> Result<(), FullTextParserError> {
-        if let Some(mut root) = document.get_root_element() {
-            Self::simplify_nested_elements(&mut root)?;
-            Self::clean_attributes(&mut root)?;
-            Self::remove_single_cell_tables(&mut root);
-            Self::remove_extra_p_and_div(&mut root);
+    /// Run the document passes of `options` on the complete article
+    pub(crate) fn post_process_document(
+        document: &Document,
+        config: Option<&ConfigEntry>,
+        global_config: &ConfigEntry,
+        url: &Url,
+        options: &ExtractionOptions,
+    ) -> Result<(), FullTextParserError> {
+        if let Some(root) = document.get_root_element() {
+            let context = Self::get_xpath_ctx(document)?;
+            options.document_passes.run(&PassContext {
+                document,
+                context: &context,
+                root,
+                config,
+                global_config,
+                url,
+                title: None,
+                options,
+            })?;
         }
 
         Ok(())
     }
 
-    pub(crate) fn post_process_page(node: &mut Node) -> Result<(), FullTextParserError> {
-        Util::clean_headers(node);
-        Util::replace_schema_org_orbjects(node);
-        Util::clean_conditionally(node, "fieldset");
-        Util::clean_conditionally(node, "table");
-        Util::clean_conditionally(node, "ul");
-        Util::clean_conditionally(node, "div");
-
-        Self::remove_share_elements(node);
-        Self::clean_attributes(node)?;
-        Self::remove_single_cell_tables(node);
-        Self::remove_extra_p_and_div(node);
-        Self::remove_empty_nodes(node);
-
-        Ok(())
+    /// Clean up the extracted body `ctx.root` with the page passes of `ctx.options`
+    pub(crate) fn post_process_page(ctx: &PassContext) -> Result<(), FullTextParserError> {
+        ctx.options.page_passes.run(ctx)
     }
 
     fn remove_single_cell_tables(root: &mut Node) {
@@ -1022,13 +1607,19 @@ impl FullTextParser {
         }
     }
 
-    fn clean_attributes(root: &mut Node) -> Result<(), FullTextParserError> {
+    fn clean_attributes(
+        root: &mut Node,
+        options: &ExtractionOptions,
+    ) -> Result<(), FullTextParserError> {
         let mut node_iter = Some(root.clone());
 
         while let Some(mut node) = node_iter {
             let tag_name = node.get_name().to_uppercase();
 
             for attr in constants::PRESENTATIONAL_ATTRIBUTES {
+                if *attr == "style" && !options.strip_styles {
+                    continue;
+                }
                 _ = node.remove_attribute(attr);
             }
 
@@ -1058,6 +1649,12 @@ impl FullTextParser {
                     FullTextParserError::Xml
                 })?;
 
+            node.remove_attribute(constants::ALLOWED_VIDEO_ATTR)
+                .map_err(|e| {
+                    log::error!("{e}");
+                    FullTextParserError::Xml
+                })?;
+
             node_iter = Util::next_node(&node, false);
         }
         Ok(())
@@ -1087,7 +1684,10 @@ impl FullTextParser {
             {
                 if let Some(mut parent) = node.get_parent() {
                     if let Some(mut child) = node.get_child_elements().into_iter().next() {
-                        for (k, v) in node.get_attributes().into_iter() {
+                        // sorted to keep the output deterministic
+                        let mut attributes: Vec<_> = node.get_attributes().into_iter().collect();
+                        attributes.sort();
+                        for (k, v) in attributes {
                             child.set_attribute(&k, &v).map_err(|e| {
                                 log::error!("{e}");
                                 FullTextParserError::Xml
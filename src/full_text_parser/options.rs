@@ -0,0 +1,53 @@
+use super::Passes;
+use crate::constants;
+use regex::Regex;
+
+/// Switches and passes for the cleanup applied to every page before and after the
+/// article is extracted. All steps are enabled by default.
+#[derive(Clone, Debug)]
+pub struct ExtractionOptions {
+    /// Strip `<footer>` elements
+    pub strip_footers: bool,
+    /// Strip `<aside>` elements
+    pub strip_asides: bool,
+    /// Strip `<iframe>`, `<object>` and `<embed>` elements that don't match `allowed_video`
+    pub strip_embeds: bool,
+    /// Strip `<form>`, `<input>`, `<textarea>`, `<select>` and `<button>` elements
+    pub strip_forms: bool,
+    /// Strip all `style` attributes
+    pub strip_styles: bool,
+    /// Rename `<h1>` to `<h2>`, as the title is usually displayed separately
+    pub h1_to_h2: bool,
+    /// Rename `<font>` to `<span>`
+    pub font_to_span: bool,
+    /// Minimum number of characters the readability algorithm expects in an article
+    pub char_threshold: usize,
+    /// Iframes, embeds and videos whose source matches are kept.
+    /// Defaults to YouTube, Vimeo, Dailymotion, PeerTube and a few others.
+    pub allowed_video: Regex,
+    /// Applied to every page before the body is extracted
+    pub prepare_passes: Passes,
+    /// Applied to every extracted body
+    pub page_passes: Passes,
+    /// Applied to the complete article
+    pub document_passes: Passes,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            strip_footers: true,
+            strip_asides: true,
+            strip_embeds: true,
+            strip_forms: true,
+            strip_styles: true,
+            h1_to_h2: true,
+            font_to_span: true,
+            char_threshold: constants::DEFAULT_CHAR_THRESHOLD,
+            allowed_video: constants::VIDEOS.clone(),
+            prepare_passes: Passes::prepare(),
+            page_passes: Passes::page(),
+            document_passes: Passes::document(),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use url::Url;
+
+/// Supplies the HTML of additional pages of an article.
+///
+/// `FullTextParser` doesn't do any network requests on its own. Implement this
+/// trait with your own fetcher to let it follow `next_page_link` rules of the
+/// site configs. Closures of the form `FnMut(&Url) -> Option<String>` implement
+/// it as well.
+pub trait PageProvider {
+    /// Return the HTML of the page at `url` or `None` if it could not be fetched
+    fn get_page(&mut self, url: &Url) -> Option<String>;
+}
+
+impl<F> PageProvider for F
+where
+    F: FnMut(&Url) -> Option<String>,
+{
+    fn get_page(&mut self, url: &Url) -> Option<String> {
+        self(url)
+    }
+}
@@ -0,0 +1,496 @@
+use super::config::ConfigEntry;
+use super::error::FullTextParserError;
+use super::trace::{self, TraceEvent};
+use super::{ExtractionOptions, FullTextParser};
+use crate::util::Util;
+use libxml::tree::{Document, Node};
+use libxml::xpath::Context;
+use std::fmt;
+use std::sync::Arc;
+use url::Url;
+
+/// Everything a [`Pass`] gets to work with
+pub struct PassContext<'a> {
+    /// Document the pass modifies
+    pub document: &'a Document,
+    /// XPath context of `document`. For the page passes this is still the whole page,
+    /// use XPaths relative to `root` such as `.//img` to only touch the extracted body.
+    pub context: &'a Context,
+    /// Node the pass works on: the root of the page for the prepare passes, an extracted
+    /// body for the page passes and the complete article for the document passes
+    pub root: Node,
+    /// Site config of the page, if one was found
+    pub config: Option<&'a ConfigEntry>,
+    /// `global.txt` or an empty config if `autodetect_on_failure` is disabled
+    pub global_config: &'a ConfigEntry,
+    /// URL relative links are resolved against
+    pub url: &'a Url,
+    /// Title of the article, if already known
+    pub title: Option<&'a str>,
+    pub options: &'a ExtractionOptions,
+}
+
+/// A step modifying the DOM of a page or article
+pub trait Pass: Send + Sync {
+    /// Name used to find the pass in [`Passes`]
+    fn name(&self) -> &str;
+
+    fn run(&self, ctx: &PassContext) -> Result<(), FullTextParserError>;
+}
+
+/// Implements [`Pass`] for a function or closure
+pub struct FnPass<F> {
+    name: &'static str,
+    run: F,
+}
+
+impl<F> FnPass<F>
+where
+    F: Fn(&PassContext) -> Result<(), FullTextParserError> + Send + Sync,
+{
+    pub fn new(name: &'static str, run: F) -> Self {
+        Self { name, run }
+    }
+}
+
+impl<F> Pass for FnPass<F>
+where
+    F: Fn(&PassContext) -> Result<(), FullTextParserError> + Send + Sync,
+{
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn run(&self, ctx: &PassContext) -> Result<(), FullTextParserError> {
+        (self.run)(ctx)
+    }
+}
+
+type PassFn = fn(&PassContext) -> Result<(), FullTextParserError>;
+
+/// Ordered list of passes. `prepare`, `page` and `document` return the built-in passes
+/// of each stage, which can be extended, reordered or removed by name.
+#[derive(Clone, Default)]
+pub struct Passes {
+    passes: Vec<Arc<dyn Pass>>,
+}
+
+impl Passes {
+    /// Passes applied to every page before the body is extracted
+    pub fn prepare() -> Self {
+        Self::builtin(&[
+            ("h1_to_h2", h1_to_h2),
+            ("remove_title_headers", remove_title_headers),
+            ("font_to_span", font_to_span),
+            ("mark_data_tables", mark_data_tables),
+            ("strip_xpath", strip_xpath),
+            ("strip_id_or_class", strip_id_or_class),
+            ("strip_image_src", strip_image_src),
+            ("strip_attr", strip_attr),
+            ("restructure", restructure),
+            ("unwrap_noscript_images", unwrap_noscript_images),
+            ("fix_lazy_images", fix_lazy_images),
+            ("fix_video_embeds", fix_video_embeds),
+            ("remove_unused_attributes", remove_unused_attributes),
+            ("strip_ignored_classes", strip_ignored_classes),
+            ("strip_hidden", strip_hidden),
+            ("strip_styles", strip_styles),
+            ("strip_forms", strip_forms),
+            ("strip_scripts", strip_scripts),
+            ("strip_footers", strip_footers),
+            ("strip_asides", strip_asides),
+            ("replace_brs", replace_brs),
+            ("replace_emoji_images", replace_emoji_images),
+            ("fix_urls", fix_urls),
+        ])
+    }
+
+    /// Passes applied to every extracted body
+    pub fn page() -> Self {
+        Self::builtin(&[
+            ("clean_headers", clean_headers),
+            ("replace_schema_org_objects", replace_schema_org_objects),
+            ("clean_conditionally", clean_conditionally),
+            ("remove_share_elements", remove_share_elements),
+            ("clean_attributes", clean_attributes),
+            ("remove_single_cell_tables", remove_single_cell_tables),
+            ("remove_extra_p_and_div", remove_extra_p_and_div),
+            ("remove_empty_nodes", remove_empty_nodes),
+        ])
+    }
+
+    /// Passes applied to the complete article
+    pub fn document() -> Self {
+        Self::builtin(&[
+            ("simplify_nested_elements", simplify_nested_elements),
+            ("clean_attributes", clean_attributes),
+            ("remove_single_cell_tables", remove_single_cell_tables),
+            ("remove_extra_p_and_div", remove_extra_p_and_div),
+        ])
+    }
+
+    fn builtin(passes: &[(&'static str, PassFn)]) -> Self {
+        let passes = passes
+            .iter()
+            .map(|&(name, run)| Arc::new(FnPass::new(name, run)) as Arc<dyn Pass>)
+            .collect();
+        Self { passes }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    pub fn push(&mut self, pass: Arc<dyn Pass>) {
+        self.passes.push(pass);
+    }
+
+    /// Insert `pass` before the pass called `name`. Returns `false` if there is none.
+    pub fn insert_before(&mut self, name: &str, pass: Arc<dyn Pass>) -> bool {
+        self.insert_at(name, 0, pass)
+    }
+
+    /// Insert `pass` after the pass called `name`. Returns `false` if there is none.
+    pub fn insert_after(&mut self, name: &str, pass: Arc<dyn Pass>) -> bool {
+        self.insert_at(name, 1, pass)
+    }
+
+    /// Remove the pass called `name` and return it, e.g. to insert it somewhere else
+    pub fn remove(&mut self, name: &str) -> Option<Arc<dyn Pass>> {
+        let index = self.position(name)?;
+        Some(self.passes.remove(index))
+    }
+
+    /// Run all passes in order. A failing pass is logged and the remaining passes
+    /// still run, the first error is returned once all of them are done.
+    pub(crate) fn run(&self, ctx: &PassContext) -> Result<(), FullTextParserError> {
+        let mut result = Ok(());
+        for pass in &self.passes {
+            if let Err(error) = pass.run(ctx) {
+                log::error!("Pass '{}' failed: {error}", pass.name());
+                if result.is_ok() {
+                    result = Err(error);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn insert_at(&mut self, name: &str, offset: usize, pass: Arc<dyn Pass>) -> bool {
+        match self.position(name) {
+            Some(index) => {
+                self.passes.insert(index + offset, pass);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.passes.iter().position(|pass| pass.name() == name)
+    }
+}
+
+impl fmt::Debug for Passes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// Site config and `global.txt`
+fn configs<'a>(ctx: &PassContext<'a>) -> impl Iterator<Item = &'a ConfigEntry> {
+    ctx.config.into_iter().chain(Some(ctx.global_config))
+}
+
+// The prepare passes ignore errors of single xpath expressions, so that a broken
+// rule of a site config doesn't prevent the remaining rules from being applied.
+
+/// Replace H1 with H2 as H1 should be only title that is displayed separately
+fn h1_to_h2(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if ctx.options.h1_to_h2 {
+        if let Ok(h1_nodes) = Util::evaluate_xpath(ctx.context, "//h1", false) {
+            for mut h1_node in h1_nodes {
+                _ = h1_node.set_name("h2");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn remove_title_headers(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if let Ok(h2_nodes) = Util::evaluate_xpath(ctx.context, "//h2", false) {
+        for mut h2_node in h2_nodes {
+            if !h2_node.is_null() && Util::header_duplicates_title(&h2_node, ctx.title) {
+                h2_node.unlink();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn font_to_span(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if ctx.options.font_to_span {
+        if let Ok(font_nodes) = Util::evaluate_xpath(ctx.context, "//font", false) {
+            for mut font_node in font_nodes {
+                _ = font_node.set_name("span");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn mark_data_tables(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    _ = Util::mark_data_tables(ctx.context);
+    Ok(())
+}
+
+/// `strip` rules of the site config
+fn strip_xpath(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    for xpath_strip in configs(ctx).flat_map(|config| &config.xpath_strip) {
+        let nodes = Util::strip_node(ctx.context, xpath_strip, ctx.options);
+        record_strip("strip", xpath_strip, nodes);
+    }
+    Ok(())
+}
+
+/// Strip everything with specified 'id' or 'class'
+fn strip_id_or_class(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    for id_or_class in configs(ctx).flat_map(|config| &config.strip_id_or_class) {
+        let nodes = Util::strip_id_or_class(ctx.context, id_or_class);
+        record_strip("strip_id_or_class", id_or_class, nodes);
+    }
+    Ok(())
+}
+
+/// Strip any <img> element where @src attribute contains this substring
+fn strip_image_src(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    for image_src in configs(ctx).flat_map(|config| &config.strip_image_src) {
+        let xpath = format!("//img[contains(@src,'{image_src}')]");
+        let nodes = Util::strip_node(ctx.context, &xpath, ctx.options);
+        record_strip("strip_image_src", image_src, nodes);
+    }
+    Ok(())
+}
+
+/// Strip attributes matching the specified xpath
+fn strip_attr(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    for xpath_strip_attr in configs(ctx).flat_map(|config| &config.strip_attr) {
+        let nodes = Util::strip_attribute(ctx.context, xpath_strip_attr);
+        record_strip("strip_attr", xpath_strip_attr, nodes);
+    }
+    Ok(())
+}
+
+/// A rule that failed, e.g. because of an invalid XPath, removed nothing
+fn record_strip(directive: &'static str, rule: &str, nodes: Result<usize, FullTextParserError>) {
+    trace::record(|| TraceEvent::Strip {
+        directive,
+        rule: rule.into(),
+        nodes: nodes.unwrap_or(0),
+    });
+}
+
+/// `wrap_in`, `dissolve` and `move_into` rules of the site config
+fn restructure(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    for config in configs(ctx) {
+        for wrap_in in &config.wrap_in {
+            let nodes = Util::wrap_in(ctx.context, ctx.document, &wrap_in.tag, &wrap_in.xpath);
+            record_restructure("wrap_in", &wrap_in.xpath, nodes);
+        }
+
+        for xpath_dissolve in &config.dissolve {
+            let nodes = Util::dissolve(ctx.context, xpath_dissolve);
+            record_restructure("dissolve", xpath_dissolve, nodes);
+        }
+
+        for move_into in &config.move_into {
+            let nodes = Util::move_into(
+                ctx.context,
+                ctx.document,
+                &move_into.target,
+                &move_into.xpath,
+            );
+            record_restructure("move_into", &move_into.xpath, nodes);
+        }
+    }
+    Ok(())
+}
+
+/// A rule that failed, e.g. because of a `move_into` target that doesn't exist, moved nothing
+fn record_restructure(
+    directive: &'static str,
+    rule: &str,
+    nodes: Result<usize, FullTextParserError>,
+) {
+    trace::record(|| TraceEvent::Restructure {
+        directive,
+        rule: rule.into(),
+        nodes: nodes.unwrap_or(0),
+    });
+}
+
+fn unwrap_noscript_images(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    _ = FullTextParser::unwrap_noscript_images(ctx.context);
+    _ = Util::strip_node(ctx.context, "//noscript", ctx.options);
+    Ok(())
+}
+
+fn fix_lazy_images(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    let lazy_load_attr = ctx
+        .config
+        .and_then(|config| config.src_lazy_load_attr.as_deref())
+        .or(ctx.global_config.src_lazy_load_attr.as_deref());
+    _ = FullTextParser::fix_lazy_images(ctx.context, ctx.document, lazy_load_attr);
+    Ok(())
+}
+
+fn fix_video_embeds(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    _ = FullTextParser::fix_video_embeds(ctx.context, ctx.options);
+    Ok(())
+}
+
+fn remove_unused_attributes(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    _ = FullTextParser::remove_attribute(ctx.context, Some("a"), "onclick");
+    _ = FullTextParser::remove_attribute(ctx.context, Some("img"), "decoding");
+    _ = FullTextParser::remove_attribute(ctx.context, Some("img"), "loading");
+    Ok(())
+}
+
+/// Strip elements using Readability.com and Instapaper.com ignore class names
+/// .entry-unrelated and .instapaper_ignore
+/// See http://blog.instapaper.com/post/730281947
+fn strip_ignored_classes(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    _ = Util::strip_node(
+        ctx.context,
+        "//*[contains(@class,' entry-unrelated ') or contains(@class,' instapaper_ignore ')]",
+        ctx.options,
+    );
+    Ok(())
+}
+
+/// Strip elements that contain style="display: none;"
+fn strip_hidden(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    _ = Util::strip_node(
+        ctx.context,
+        "//*[contains(@style,'display:none')]",
+        ctx.options,
+    );
+    _ = Util::strip_node(
+        ctx.context,
+        "//*[contains(@style,'display: none')]",
+        ctx.options,
+    );
+    Ok(())
+}
+
+fn strip_styles(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if ctx.options.strip_styles {
+        _ = FullTextParser::remove_attribute(ctx.context, None, "style");
+    }
+    Ok(())
+}
+
+/// Strip all input elements
+fn strip_forms(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if ctx.options.strip_forms {
+        for xpath in ["//form", "//input", "//textarea", "//select", "//button"] {
+            _ = Util::strip_node(ctx.context, xpath, ctx.options);
+        }
+    }
+    Ok(())
+}
+
+/// Strip comments, scripts, styles, empty links and external css and fonts
+fn strip_scripts(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    for xpath in [
+        "//comment()",
+        "//script",
+        "//style",
+        "//a[not(node())]",
+        "//*[@type='text/css']",
+        "//link",
+    ] {
+        _ = Util::strip_node(ctx.context, xpath, ctx.options);
+    }
+    Ok(())
+}
+
+fn strip_footers(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if ctx.options.strip_footers {
+        _ = Util::strip_node(ctx.context, "//footer", ctx.options);
+    }
+    Ok(())
+}
+
+fn strip_asides(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if ctx.options.strip_asides {
+        _ = Util::strip_node(ctx.context, "//aside", ctx.options);
+    }
+    Ok(())
+}
+
+fn replace_brs(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    Util::replace_brs(&ctx.root, ctx.document);
+    Ok(())
+}
+
+fn replace_emoji_images(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    Util::replace_emoji_images(&ctx.root, ctx.document);
+    Ok(())
+}
+
+fn fix_urls(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    FullTextParser::fix_urls(ctx.context, ctx.url, ctx.document);
+    Ok(())
+}
+
+fn clean_headers(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    Util::clean_headers(&mut ctx.root.clone());
+    Ok(())
+}
+
+fn replace_schema_org_objects(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    Util::replace_schema_org_orbjects(&mut ctx.root.clone());
+    Ok(())
+}
+
+/// Remove elements that look like they aren't content unless the site config
+/// disables it with `prune: no`
+fn clean_conditionally(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    if ctx.config.map(ConfigEntry::prune).unwrap_or(true) {
+        let mut root = ctx.root.clone();
+        for tag in ["fieldset", "table", "ul", "div"] {
+            Util::clean_conditionally(&mut root, tag, &ctx.options.allowed_video);
+        }
+    }
+    Ok(())
+}
+
+fn remove_share_elements(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    FullTextParser::remove_share_elements(&mut ctx.root.clone());
+    Ok(())
+}
+
+fn clean_attributes(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    FullTextParser::clean_attributes(&mut ctx.root.clone(), ctx.options)
+}
+
+fn remove_single_cell_tables(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    FullTextParser::remove_single_cell_tables(&mut ctx.root.clone());
+    Ok(())
+}
+
+fn remove_extra_p_and_div(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    FullTextParser::remove_extra_p_and_div(&mut ctx.root.clone());
+    Ok(())
+}
+
+fn remove_empty_nodes(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    FullTextParser::remove_empty_nodes(&mut ctx.root.clone());
+    Ok(())
+}
+
+fn simplify_nested_elements(ctx: &PassContext) -> Result<(), FullTextParserError> {
+    FullTextParser::simplify_nested_elements(&mut ctx.root.clone())
+}
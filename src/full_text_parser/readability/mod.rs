@@ -5,6 +5,9 @@ mod tests;
 
 use self::state::State;
 use super::error::FullTextParserError;
+use super::trace::{self, TraceEvent};
+use super::{ExtractionOptions, PassContext};
+use crate::article::{Article, Author, MetadataSource};
 use crate::{constants, util::Util};
 use libxml::tree::{Document, Node};
 use std::cmp::Ordering;
@@ -23,7 +26,75 @@ impl Readability {
     /// * `base_url` - URL used to complete relative URLs
     ///
     pub fn extract(html: &str, base_url: Option<url::Url>) -> Result<String, FullTextParserError> {
-        libxml::tree::node::set_node_rc_guard(10);
+        Self::extract_with_options(html, base_url, &ExtractionOptions::default())
+    }
+
+    /// Like `extract`, but with `options` to control which cleanup steps are applied
+    pub fn extract_with_options(
+        html: &str,
+        base_url: Option<url::Url>,
+        options: &ExtractionOptions,
+    ) -> Result<String, FullTextParserError> {
+        Self::extract_article_with_options(html, base_url, options)?
+            .html
+            .ok_or(FullTextParserError::Readability)
+    }
+
+    /// Like `extract_article`, but takes the raw bytes of the page and detects their encoding
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - Bytes of a website containing an article or similar content
+    /// * `content_type` - `Content-Type` header the page was served with
+    /// * `base_url` - URL used to complete relative URLs
+    ///
+    pub fn extract_article_bytes(
+        html: &[u8],
+        content_type: Option<&str>,
+        base_url: Option<url::Url>,
+    ) -> Result<Article, FullTextParserError> {
+        Self::extract_article_bytes_with_options(
+            html,
+            content_type,
+            base_url,
+            &ExtractionOptions::default(),
+        )
+    }
+
+    /// Like `extract_article_bytes`, but with `options` to control which cleanup steps are applied
+    pub fn extract_article_bytes_with_options(
+        html: &[u8],
+        content_type: Option<&str>,
+        base_url: Option<url::Url>,
+        options: &ExtractionOptions,
+    ) -> Result<Article, FullTextParserError> {
+        let (html, encoding) = crate::full_text_parser::decode_html(html, content_type);
+        let mut article = Self::extract_article_with_options(&html, base_url, options)?;
+        article.encoding = Some(encoding.name().into());
+        Ok(article)
+    }
+
+    /// Parse HTML and extract the article including its metadata: title, author, date,
+    /// thumbnail, excerpt, site name, language, text direction and text length
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - HTML of a website containing an article or similar content
+    /// * `base_url` - URL used to complete relative URLs
+    ///
+    pub fn extract_article(
+        html: &str,
+        base_url: Option<url::Url>,
+    ) -> Result<Article, FullTextParserError> {
+        Self::extract_article_with_options(html, base_url, &ExtractionOptions::default())
+    }
+
+    /// Like `extract_article`, but with `options` to control which cleanup steps are applied
+    pub fn extract_article_with_options(
+        html: &str,
+        base_url: Option<url::Url>,
+        options: &ExtractionOptions,
+    ) -> Result<Article, FullTextParserError> {
         let empty_config = crate::full_text_parser::config::ConfigEntry::default();
 
         let url =
@@ -31,37 +102,129 @@ impl Readability {
         let document = crate::FullTextParser::parse_html(html, None, &empty_config)?;
         let xpath_ctx = crate::FullTextParser::get_xpath_ctx(&document)?;
 
-        crate::FullTextParser::prep_content(&xpath_ctx, None, &empty_config, &url, &document, None);
-        let mut article = crate::article::Article {
-            title: None,
-            author: None,
-            url,
-            date: None,
-            thumbnail_url: None,
-            html: None,
-        };
+        let mut article = Article::new(url);
+        // metadata has to be extracted before `prep_content` strips the JSON-LD scripts
+        crate::full_text_parser::metadata::extract(&xpath_ctx, None, None, &mut article);
+        if article.thumbnail_url.is_none() {
+            article.thumbnail_url = crate::FullTextParser::check_for_thumbnail(&xpath_ctx);
+        }
+
+        crate::FullTextParser::prep_content(
+            &xpath_ctx,
+            None,
+            &empty_config,
+            &article.url,
+            &document,
+            None,
+            options,
+        );
 
         let mut article_document = Document::new().map_err(|()| FullTextParserError::Xml)?;
         let mut root =
             Node::new("article", None, &document).map_err(|()| FullTextParserError::Xml)?;
         article_document.set_root_element(&root);
 
-        crate::full_text_parser::metadata::extract(&xpath_ctx, None, None, &mut article);
-        super::Readability::extract_body(document, &mut root, article.title.as_deref())?;
-        crate::FullTextParser::post_process_document(&article_document)?;
+        super::Readability::extract_body(document, &mut root, &mut article, options)?;
+        crate::FullTextParser::post_process_document(
+            &article_document,
+            None,
+            &empty_config,
+            &article.url,
+            options,
+        )?;
+
+        // `root` still refers to the source document that `extract_body` consumed
+        if let Some(root) = article_document.get_root_element() {
+            crate::full_text_parser::metadata::extract_from_body(&root, &mut article);
+        }
+        article.html = Some(Util::serialize_node(&article_document, &root));
 
-        let html = Util::serialize_node(&article_document, &root);
-        article.html.replace(html.clone());
+        Ok(article)
+    }
 
-        Ok(html)
+    /// Port of `isProbablyReaderable` of Mozilla Readability: a quick guess whether `html`
+    /// contains an article, without extracting it
+    pub fn is_probably_readerable(html: &str) -> bool {
+        let empty_config = crate::full_text_parser::config::ConfigEntry::default();
+        let Ok(document) = crate::FullTextParser::parse_html(html, None, &empty_config) else {
+            return false;
+        };
+        let Ok(context) = crate::FullTextParser::get_xpath_ctx(&document) else {
+            return false;
+        };
+
+        let mut nodes =
+            Util::evaluate_xpath(&context, "//p | //pre | //article", false).unwrap_or_default();
+        // divs with line breaks instead of paragraphs
+        for br in Util::evaluate_xpath(&context, "//div/br", false).unwrap_or_default() {
+            if let Some(parent) = br.get_parent() {
+                if !nodes.contains(&parent) {
+                    nodes.push(parent);
+                }
+            }
+        }
+
+        let mut score = 0.0;
+        nodes.iter().any(|node| {
+            let display_none = node
+                .get_attribute("style")
+                .is_some_and(|style| style.replace(' ', "").contains("display:none"));
+            if display_none || !Util::is_probably_visible(node) {
+                return false;
+            }
+
+            let match_string = Util::get_signature(node);
+            if constants::UNLIELY_CANDIDATES.is_match(&match_string)
+                && !constants::OKAY_MAYBE_ITS_A_CANDIDATE.is_match(&match_string)
+            {
+                return false;
+            }
+
+            if node.get_name().eq_ignore_ascii_case("p")
+                && Util::has_ancestor_tag(node, "li", Some(u64::MAX), None::<fn(&Node) -> bool>)
+            {
+                return false;
+            }
+
+            // length in UTF-16 code units like `textContent.length`
+            let text_length = node.get_content().trim().encode_utf16().count();
+            if text_length < constants::READERABLE_MIN_CONTENT_LENGTH {
+                return false;
+            }
+
+            score += ((text_length - constants::READERABLE_MIN_CONTENT_LENGTH) as f64).sqrt();
+            score > constants::READERABLE_MIN_SCORE
+        })
     }
 
+    /// Extract the content of `document` into `root`. A byline found along the way
+    /// becomes the author of `article` if no other source provided one.
     pub(crate) fn extract_body(
         document: Document,
         root: &mut Node,
-        title: Option<&str>,
+        article: &mut Article,
+        options: &ExtractionOptions,
     ) -> Result<bool, FullTextParserError> {
         let mut state = State::default();
+        let found_body = Self::grab_article(document, root, article, options, &mut state)?;
+
+        if article.author.is_none() {
+            if let Some(byline) = state.byline {
+                log::debug!("Article author from byline: '{byline}'");
+                article.set_authors(vec![Author::new(byline)], MetadataSource::Byline);
+            }
+        }
+
+        Ok(found_body)
+    }
+
+    fn grab_article(
+        document: Document,
+        root: &mut Node,
+        article: &Article,
+        options: &ExtractionOptions,
+        state: &mut State,
+    ) -> Result<bool, FullTextParserError> {
         let mut document = document;
         let mut attempts: Vec<(Node, usize, Document)> = Vec::new();
         let document_cache = document
@@ -88,13 +251,13 @@ impl Readability {
                     continue;
                 }
 
-                if Self::check_byline(node_ref, &match_string, &mut state) {
+                if Self::check_byline(node_ref, &match_string, state) {
                     node = Util::remove_and_next(node_ref);
                     continue;
                 }
 
                 if state.should_remove_title_header
-                    && Util::header_duplicates_title(node_ref, title)
+                    && Util::header_duplicates_title(node_ref, article.title.as_deref())
                 {
                     state.should_remove_title_header = false;
                     node = Util::remove_and_next(node_ref);
@@ -275,7 +438,7 @@ impl Readability {
                     }
 
                     if Self::get_content_score(&ancestor).is_none() {
-                        Self::initialize_node(&mut ancestor, &state)?;
+                        Self::initialize_node(&mut ancestor, state)?;
                         candidates.push(ancestor.clone());
                     }
 
@@ -362,8 +525,7 @@ impl Readability {
 
                 root.add_child(&mut new_top_candidate).unwrap();
 
-                Self::initialize_node(&mut new_top_candidate, &state)
-                    .expect("init should not fail");
+                Self::initialize_node(&mut new_top_candidate, state).expect("init should not fail");
                 needed_to_create_top_candidate = true;
                 new_top_candidate
             });
@@ -411,7 +573,7 @@ impl Readability {
             }
 
             if Self::get_content_score(&top_candidate).is_none() {
-                Self::initialize_node(&mut top_candidate, &state)?;
+                Self::initialize_node(&mut top_candidate, state)?;
             }
 
             //Util::serialize_node(&top_candidate, "new_top_candidate.html");
@@ -479,7 +641,7 @@ impl Readability {
             }
 
             if Self::get_content_score(&top_candidate).is_none() {
-                Self::initialize_node(&mut top_candidate, &state)?;
+                Self::initialize_node(&mut top_candidate, state)?;
             }
 
             // Now that we have the top candidate, look through its siblings for content
@@ -584,7 +746,17 @@ impl Readability {
             }
 
             if state.clean_conditionally {
-                crate::FullTextParser::post_process_page(&mut article_content)?;
+                let context = crate::FullTextParser::get_xpath_ctx(&document)?;
+                crate::FullTextParser::post_process_page(&PassContext {
+                    document: &document,
+                    context: &context,
+                    root: article_content.clone(),
+                    config: None,
+                    global_config: &super::EMPTY_CONFIG,
+                    url: &article.url,
+                    title: article.title.as_deref(),
+                    options,
+                })?;
             }
 
             if needed_to_create_top_candidate {
@@ -634,7 +806,15 @@ impl Readability {
             let text = Util::get_inner_text(&article_content, true);
             let text_length = text.len();
 
-            if text_length < constants::DEFAULT_CHAR_THRESHOLD {
+            trace::record(|| TraceEvent::ReadabilityAttempt {
+                strip_unlikely: state.strip_unlikely,
+                weigh_classes: state.weigh_classes,
+                clean_conditionally: state.clean_conditionally,
+                text_length,
+                accepted: text_length >= options.char_threshold,
+            });
+
+            if text_length < options.char_threshold {
                 parse_successful = false;
 
                 if state.strip_unlikely {
@@ -650,10 +830,11 @@ impl Readability {
                     attempts.push((article_content, text_length, document));
                     // No luck after removing flags, just return the longest text we found during the different loops
 
-                    attempts.sort_by(|(_, size_a, _), (_, size_b, _)| size_a.cmp(size_b));
+                    attempts.sort_by_key(|(_, size, _)| *size);
 
                     // But first check if we actually have something
-                    if let Some((best_attempt, _len, _document)) = attempts.pop() {
+                    if let Some((best_attempt, text_length, _document)) = attempts.pop() {
+                        trace::record(|| TraceEvent::ReadabilityLongestAttempt { text_length });
                         for mut child in best_attempt.get_child_nodes() {
                             if child.is_null() {
                                 continue;
@@ -4,10 +4,143 @@ use crate::{
     util::Util,
 };
 use libxml::tree::{Document, Node};
+use serde::Deserialize;
 use url::Url;
 
+/// `expected-metadata.json` of a test page, copied unchanged from Mozilla Readability
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExpectedMetadata {
+    title: Option<String>,
+    byline: Option<String>,
+    excerpt: Option<String>,
+    site_name: Option<String>,
+    /// Only compared if the file has it, like upstream does
+    dir: Option<String>,
+    lang: Option<String>,
+    published_time: Option<String>,
+    readerable: bool,
+}
+
+/// Metadata fields of test pages where this crate knowingly differs from Mozilla
+/// Readability, as `(page, field)`. They are expected to fail: once one matches, it
+/// has to be removed from the list.
+const METADATA_DIVERGENCES: &[(&str, &str)] = &[
+    ("002", "title"),
+    ("003", "title"),
+    ("003", "byline"),
+    ("aclu", "excerpt"),
+    ("archive-of-our-own", "byline"),
+    ("ars-1", "byline"),
+    ("base-url-base-element-relative", "excerpt"),
+    ("basic-tags-cleaning", "excerpt"),
+    ("bbc-1", "byline"),
+    ("blogger", "title"),
+    ("blogger", "byline"),
+    ("breitbart", "title"),
+    ("bug-1255978", "title"),
+    ("citylab-1", "title"),
+    ("clean-links", "excerpt"),
+    ("cnn", "byline"),
+    ("comment-inside-script-parsing", "excerpt"),
+    ("dev418", "title"),
+    ("dropbox-blog", "title"),
+    ("dropbox-blog", "byline"),
+    ("ebb-org", "title"),
+    ("ebb-org", "byline"),
+    ("ebb-org", "excerpt"),
+    ("ehow-1", "title"),
+    ("ehow-1", "byline"),
+    ("ehow-2", "title"),
+    ("ehow-2", "byline"),
+    ("embedded-videos", "excerpt"),
+    ("firefox-nightly-blog", "title"),
+    ("firefox-nightly-blog", "byline"),
+    ("folha", "excerpt"),
+    ("gmw", "byline"),
+    ("google-sre-book-1", "title"),
+    ("guardian-1", "title"),
+    ("heise", "byline"),
+    ("hidden-nodes", "title"),
+    ("hukumusume", "title"),
+    ("iab-1", "title"),
+    ("ietf-1", "title"),
+    ("ietf-1", "byline"),
+    ("keep-images", "title"),
+    ("keep-tabular-data", "title"),
+    ("la-nacion", "title"),
+    ("lazy-image-1", "excerpt"),
+    ("lemonde-1", "byline"),
+    ("liberation-1", "byline"),
+    ("lifehacker-post-comment-load", "byline"),
+    ("lifehacker-working", "byline"),
+    ("links-in-tables", "byline"),
+    ("links-in-tables", "excerpt"),
+    ("medicalnewstoday", "title"),
+    ("medium-3", "title"),
+    ("medium-3", "excerpt"),
+    ("mercurial", "title"),
+    ("metadata-content-missing", "title"),
+    ("metadata-content-missing", "byline"),
+    ("metadata-content-missing", "excerpt"),
+    ("missing-paragraphs", "title"),
+    ("missing-paragraphs", "byline"),
+    ("missing-paragraphs", "excerpt"),
+    ("mozilla-1", "title"),
+    ("mozilla-1", "excerpt"),
+    ("msn", "excerpt"),
+    ("normalize-spaces", "excerpt"),
+    ("nytimes-1", "byline"),
+    ("nytimes-2", "byline"),
+    ("nytimes-3", "byline"),
+    ("nytimes-4", "byline"),
+    ("pixnet", "byline"),
+    ("qq", "excerpt"),
+    ("quanta-1", "title"),
+    ("remove-aria-hidden", "excerpt"),
+    ("remove-extra-paragraphs", "excerpt"),
+    ("remove-script-tags", "excerpt"),
+    ("reordering-paragraphs", "title"),
+    ("reordering-paragraphs", "excerpt"),
+    ("replace-font-tags", "excerpt"),
+    ("salon-1", "title"),
+    ("seattletimes-1", "byline"),
+    ("social-buttons", "excerpt"),
+    ("style-tags-removal", "excerpt"),
+    ("table-style-attributes", "excerpt"),
+    ("telegraph", "byline"),
+    ("title-and-h1-discrepancy", "excerpt"),
+    ("toc-missing", "excerpt"),
+    ("tumblr", "title"),
+    ("tumblr", "byline"),
+    ("v8-blog", "title"),
+    ("videos-1", "excerpt"),
+    ("videos-2", "excerpt"),
+    ("webmd-1", "byline"),
+    ("webmd-2", "excerpt"),
+    ("wikipedia", "title"),
+    ("wikipedia-2", "title"),
+    ("wikipedia-3", "title"),
+    ("wordpress", "title"),
+    ("wordpress", "byline"),
+    ("yahoo-2", "title"),
+    ("yahoo-3", "dir"),
+    ("yahoo-4", "title"),
+    ("youth", "byline"),
+    ("youth", "excerpt"),
+];
+
 fn run_test(name: &str) {
-    libxml::tree::node::set_node_rc_guard(10);
+    run_test_with_url(name, "http://fakehost/test/base/")
+}
+
+/// Extract `resources/tests/readability/<name>/source.html` and compare the result
+/// against `expected.html` and `expected-metadata.json` in the same directory.
+///
+/// Run `UPDATE_GOLDEN=1 cargo test` to regenerate `expected.html` after an intended
+/// change of the output. `expected-metadata.json` comes from Mozilla Readability and
+/// is never regenerated, see `METADATA_DIVERGENCES`.
+fn run_test_with_url(name: &str, url: &str) {
     let _ = env_logger::builder()
         .filter_level(log::LevelFilter::Debug)
         .is_test(true)
@@ -15,46 +148,187 @@ fn run_test(name: &str) {
 
     let empty_config = ConfigEntry::default();
 
-    let url = Url::parse("http://fakehost/test/base/").unwrap();
-    let html = std::fs::read_to_string(format!("./resources/tests/readability/{name}/source.html"))
-        .expect("Failed to read source HTML");
+    let url = Url::parse(url).unwrap();
+    let source =
+        std::fs::read_to_string(format!("./resources/tests/readability/{name}/source.html"))
+            .expect("Failed to read source HTML");
 
-    let document = crate::FullTextParser::parse_html(&html, None, &empty_config).unwrap();
+    let document = crate::FullTextParser::parse_html(&source, None, &empty_config).unwrap();
     let xpath_ctx = crate::FullTextParser::get_xpath_ctx(&document).unwrap();
+    let mut article = Article::new(url.clone());
+    let options = crate::ExtractionOptions::default();
+    metadata::extract(&xpath_ctx, None, None, &mut article);
 
-    crate::FullTextParser::prep_content(&xpath_ctx, None, &empty_config, &url, &document, None);
-    let mut article = Article {
-        title: None,
-        author: None,
-        url,
-        date: None,
-        thumbnail_url: None,
-        html: None,
-    };
-
+    crate::FullTextParser::prep_content(
+        &xpath_ctx,
+        None,
+        &empty_config,
+        &url,
+        &document,
+        None,
+        &options,
+    );
     let mut article_document = Document::new().unwrap();
     let mut root = Node::new("article", None, &document).unwrap();
     article_document.set_root_element(&root);
 
-    metadata::extract(&xpath_ctx, None, None, &mut article);
-    super::Readability::extract_body(document, &mut root, article.title.as_deref()).unwrap();
+    super::Readability::extract_body(document, &mut root, &mut article, &options).unwrap();
 
     let article_ctx = crate::FullTextParser::get_xpath_ctx(&article_document).unwrap();
 
     crate::FullTextParser::prevent_self_closing_tags(&article_ctx).unwrap();
-    crate::FullTextParser::post_process_document(&article_document).unwrap();
+    crate::FullTextParser::post_process_document(
+        &article_document,
+        None,
+        &empty_config,
+        &url,
+        &options,
+    )
+    .unwrap();
+
+    if let Some(root) = article_document.get_root_element() {
+        metadata::extract_from_body(&root, &mut article);
+    }
 
     let html = Util::serialize_node(&article_document, &root);
 
-    // abuse line below to update all test results after whitespace or similar change
-    // std::fs::write(format!("./resources/tests/readability/{name}/expected.html"), &html).unwrap();
-
-    let expected = std::fs::read_to_string(format!(
-        "./resources/tests/readability/{name}/expected.html"
-    ))
-    .expect("Failed to read expected HTML");
+    let dir = format!("./resources/tests/readability/{name}");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(format!("{dir}/expected.html"), &html).unwrap();
+        return;
+    }
 
+    let expected = std::fs::read_to_string(format!("{dir}/expected.html"))
+        .expect("Failed to read expected HTML");
     assert_eq!(expected, html);
+
+    let expected_metadata = std::fs::read_to_string(format!("{dir}/expected-metadata.json"))
+        .expect("Failed to read expected-metadata.json");
+    let expected: ExpectedMetadata =
+        serde_json::from_str(&expected_metadata).expect("Failed to parse expected-metadata.json");
+    let published_time = expected
+        .published_time
+        .as_deref()
+        .map(|date| crate::full_text_parser::date::parse_date(date, None));
+
+    let readerable = super::Readability::is_probably_readerable(&source);
+    // (field, expected, actual)
+    let mut fields = vec![
+        (
+            "title",
+            format!("{:?}", expected.title),
+            format!("{:?}", article.title),
+        ),
+        (
+            "byline",
+            format!("{:?}", expected.byline),
+            format!("{:?}", article.author),
+        ),
+        (
+            "excerpt",
+            format!("{:?}", expected.excerpt),
+            format!("{:?}", article.excerpt),
+        ),
+        (
+            "siteName",
+            format!("{:?}", expected.site_name),
+            format!("{:?}", article.site_name),
+        ),
+        (
+            "readerable",
+            expected.readerable.to_string(),
+            readerable.to_string(),
+        ),
+    ];
+    if let Some(dir) = expected.dir {
+        fields.push((
+            "dir",
+            format!("{:?}", Some(dir)),
+            format!("{:?}", article.direction),
+        ));
+    }
+    if let Some(lang) = expected.lang {
+        fields.push((
+            "lang",
+            format!("{:?}", Some(lang)),
+            format!("{:?}", article.language),
+        ));
+    }
+    if let Some(published_time) = published_time {
+        fields.push((
+            "publishedTime",
+            format!("{published_time:?}"),
+            format!("{:?}", article.date),
+        ));
+    }
+
+    let failures: Vec<_> = fields
+        .into_iter()
+        .filter_map(|(field, expected, actual)| {
+            let divergence = METADATA_DIVERGENCES.contains(&(name, field));
+            match (expected == actual, divergence) {
+                (true, true) => Some(format!(
+                    "{field} of {name} matches upstream now, remove it from METADATA_DIVERGENCES"
+                )),
+                (false, false) => Some(format!(
+                    "{field} of {name} differs from upstream: expected {expected}, got {actual}"
+                )),
+                _ => None,
+            }
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn extract_article_metadata() {
+    let html = std::fs::read_to_string("./resources/tests/readability/aktualne/source.html")
+        .expect("Failed to read source HTML");
+    let article = super::Readability::extract_article(&html, None).unwrap();
+
+    assert_eq!(article.language.as_deref(), Some("cs"));
+    // JSON-LD publisher is preferred over og:site_name
+    assert_eq!(article.site_name.as_deref(), Some("Aktuálně.cz"));
+    assert_eq!(
+        article.excerpt.as_deref(),
+        Some("Zázrak jedné sezony? West Ham United dává pochybovačům stále pádnější odpovědi.")
+    );
+    assert_eq!(article.direction, None);
+    assert_eq!(article.text_length, 4598);
+    assert!(article.html.is_some());
+}
+
+#[test]
+fn is_probably_readerable() {
+    let paragraph = "A paragraph of the article, long enough to count for the score. ".repeat(4);
+    let article = format!("<html><body><p>{paragraph}</p><p>{paragraph}</p></body></html>");
+    assert!(super::Readability::is_probably_readerable(&article));
+
+    let short = "<html><body><p>Too short to be an article.</p></body></html>";
+    assert!(!super::Readability::is_probably_readerable(short));
+
+    let list = format!("<html><body><ul><li><p>{paragraph}</p></li></ul></body></html>");
+    assert!(!super::Readability::is_probably_readerable(&list));
+
+    let hidden = format!(
+        r#"<html><body><p hidden>{paragraph}</p><p class="sidebar">{paragraph}</p></body></html>"#
+    );
+    assert!(!super::Readability::is_probably_readerable(&hidden));
+}
+
+#[test]
+fn extract_article_excerpt_from_body() {
+    let html =
+        std::fs::read_to_string("./resources/tests/readability/daringfireball-1/source.html")
+            .expect("Failed to read source HTML");
+    let article = super::Readability::extract_article(&html, None).unwrap();
+
+    // no description in the page, the first paragraph of the article is used
+    assert_eq!(
+        article.excerpt.as_deref(),
+        Some("Daring Fireball is written and produced by John Gruber.")
+    );
+    assert_eq!(article.text_length, 1298);
 }
 
 #[test]
@@ -94,7 +368,11 @@ fn ars_1() {
 
 #[test]
 fn base_url_base_element_relative() {
-    run_test("base-url-base-element-relative")
+    // the relative <base href="base/"> resolves to http://fakehost/test/base/
+    run_test_with_url(
+        "base-url-base-element-relative",
+        "http://fakehost/test/page.html",
+    )
 }
 
 #[test]
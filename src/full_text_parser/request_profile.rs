@@ -0,0 +1,149 @@
+use super::config::{ConfigEntry, Header};
+
+/// A single cookie set by a `http_header(cookie)` directive
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+}
+
+/// HTTP headers the site configs expect to be sent when requesting a page
+///
+/// Merged from the `http_header(...)` directives of `global.txt` and the site config.
+/// Site specific values take precedence over global ones.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequestProfile {
+    pub user_agent: Option<String>,
+    pub cookies: Vec<Cookie>,
+    /// All remaining headers, e.g. `referer`
+    pub headers: Vec<Header>,
+}
+
+impl RequestProfile {
+    pub(crate) fn new(config: Option<&ConfigEntry>, global_config: Option<&ConfigEntry>) -> Self {
+        let mut profile = Self::default();
+
+        let headers = global_config
+            .into_iter()
+            .chain(config)
+            .flat_map(|config| config.header.iter());
+
+        for header in headers {
+            let name = header.name.to_lowercase();
+            match name.as_str() {
+                "user-agent" => profile.user_agent = Some(header.value.clone()),
+                "cookie" => {
+                    for cookie in Self::parse_cookies(&header.value) {
+                        profile.cookies.retain(|c| c.name != cookie.name);
+                        profile.cookies.push(cookie);
+                    }
+                }
+                _ => {
+                    profile
+                        .headers
+                        .retain(|h| !h.name.eq_ignore_ascii_case(&name));
+                    profile.headers.push(Header {
+                        name,
+                        value: header.value.clone(),
+                    });
+                }
+            }
+        }
+
+        profile
+    }
+
+    /// Value of the `Cookie` header containing all cookies of the profile
+    pub fn cookie_header(&self) -> Option<String> {
+        if self.cookies.is_empty() {
+            return None;
+        }
+
+        let header = self
+            .cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Some(header)
+    }
+
+    fn parse_cookies(value: &str) -> Vec<Cookie> {
+        value
+            .split(';')
+            .filter_map(|cookie| {
+                let (name, value) = cookie.split_once('=')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+
+                Some(Cookie {
+                    name: name.into(),
+                    value: value.trim().into(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cookie, RequestProfile};
+    use crate::full_text_parser::config::{ConfigEntry, Header};
+
+    fn header(name: &str, value: &str) -> Header {
+        Header {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn merge_site_and_global_headers() {
+        let global_config = ConfigEntry {
+            header: vec![
+                header("User-Agent", "global agent"),
+                header("Cookie", "consent=false; region=eu"),
+                header("Accept", "text/html"),
+            ],
+            ..Default::default()
+        };
+        let config = ConfigEntry {
+            header: vec![
+                header("user-agent", "site agent"),
+                header("cookie", "consent=true"),
+                header("referer", "https://example.com/"),
+            ],
+            ..Default::default()
+        };
+
+        let profile = RequestProfile::new(Some(&config), Some(&global_config));
+
+        assert_eq!(profile.user_agent.as_deref(), Some("site agent"));
+        assert_eq!(
+            profile.cookies,
+            vec![
+                Cookie {
+                    name: "region".into(),
+                    value: "eu".into()
+                },
+                Cookie {
+                    name: "consent".into(),
+                    value: "true".into()
+                },
+            ]
+        );
+        assert_eq!(
+            profile.cookie_header().as_deref(),
+            Some("region=eu; consent=true")
+        );
+        assert_eq!(
+            profile.headers,
+            vec![
+                header("accept", "text/html"),
+                header("referer", "https://example.com/")
+            ]
+        );
+    }
+}
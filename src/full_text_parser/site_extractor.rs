@@ -0,0 +1,408 @@
+use super::{date, FullTextParser, EMPTY_CONFIG};
+use crate::article::{Article, Author, MetadataSource};
+use crate::{constants, util::Util};
+use serde_json::{Map, Value};
+use url::Url;
+
+/// Extracts articles of pages that can't be handled with the XPath rules of a site config,
+/// e.g. single page applications that embed the article as JSON.
+///
+/// `FullTextParser` tries all matching extractors before the site config and Readability.
+/// If the content an extractor returned is empty after cleaning, the page is extracted
+/// as if there were no extractor.
+pub trait SiteExtractor: Send + Sync {
+    /// Name used to remove the extractor from a `FullTextParser`
+    fn name(&self) -> &str;
+
+    /// Whether the extractor should be tried for the page at `url`
+    fn matches(&self, url: &Url) -> bool;
+
+    /// Whether the extractor recognizes pages of any site, e.g. by the framework they are
+    /// built with. Generic extractors are skipped for pages with a site config.
+    fn generic(&self) -> bool {
+        false
+    }
+
+    /// Extract the article from the `html` of the page at `url`, or return `None` to
+    /// leave the page to the next extractor, the site config and Readability.
+    ///
+    /// The `html` of the returned article is cleaned like the body of a page and
+    /// metadata left empty is taken from the page.
+    fn extract(&self, html: &str, url: &Url) -> Option<Article>;
+}
+
+/// Extractors every `FullTextParser` starts with
+pub(crate) fn builtin() -> Vec<Box<dyn SiteExtractor>> {
+    vec![Box::new(NextData), Box::new(NuxtState)]
+}
+
+/// Limit for the nesting of the embedded JSON, which may contain cycles
+const MAX_DEPTH: usize = 32;
+
+const CONTENT_KEYS: &[&str] = &[
+    "content",
+    "contentHtml",
+    "body",
+    "bodyHtml",
+    "html",
+    "articleBody",
+];
+const TITLE_KEYS: &[&str] = &["title", "headline", "name"];
+const AUTHOR_KEYS: &[&str] = &["authors", "author", "byline"];
+const DATE_KEYS: &[&str] = &[
+    "datePublished",
+    "publishedAt",
+    "published_at",
+    "publishDate",
+    "publishedDate",
+    "date",
+    "createdAt",
+];
+const DATE_MODIFIED_KEYS: &[&str] = &["dateModified", "updatedAt", "updated_at", "modifiedDate"];
+const IMAGE_KEYS: &[&str] = &["image", "coverImage", "featuredImage", "thumbnail"];
+const EXCERPT_KEYS: &[&str] = &["excerpt", "description", "summary", "subtitle"];
+
+/// Page data of Next.js in `<script id="__NEXT_DATA__">`
+pub struct NextData;
+
+impl SiteExtractor for NextData {
+    fn name(&self) -> &str {
+        "next_data"
+    }
+
+    /// Next.js is recognized by the page content on any host
+    fn matches(&self, _url: &Url) -> bool {
+        true
+    }
+
+    fn generic(&self) -> bool {
+        true
+    }
+
+    fn extract(&self, html: &str, url: &Url) -> Option<Article> {
+        if !html.contains("__NEXT_DATA__") {
+            return None;
+        }
+
+        let data = script_content(html, "//script[@id='__NEXT_DATA__']")?;
+        let data = parse_json(&data)?;
+        article_from_json(&data, url)
+    }
+}
+
+/// State of Nuxt, either the `devalue` payload of Nuxt 3 in `<script id="__NUXT_DATA__">`
+/// or the `window.__NUXT__` assignment of Nuxt 2 if it is plain JSON
+pub struct NuxtState;
+
+impl SiteExtractor for NuxtState {
+    fn name(&self) -> &str {
+        "nuxt_state"
+    }
+
+    /// Nuxt is recognized by the page content on any host
+    fn matches(&self, _url: &Url) -> bool {
+        true
+    }
+
+    fn generic(&self) -> bool {
+        true
+    }
+
+    fn extract(&self, html: &str, url: &Url) -> Option<Article> {
+        if !html.contains("__NUXT") {
+            return None;
+        }
+
+        let state = if let Some(payload) = script_content(html, "//script[@id='__NUXT_DATA__']") {
+            match parse_json(&payload)? {
+                Value::Array(values) => unflatten(&values),
+                _ => return None,
+            }
+        } else {
+            let script = script_content(
+                html,
+                "//script[starts-with(normalize-space(.), 'window.__NUXT__')]",
+            )?;
+            let (_, state) = script.split_once('=')?;
+            parse_json(state.trim().trim_end_matches(';'))?
+        };
+
+        article_from_json(&state, url)
+    }
+}
+
+fn script_content(html: &str, xpath: &str) -> Option<String> {
+    let document = FullTextParser::parse_html(html, None, &EMPTY_CONFIG).ok()?;
+    let context = FullTextParser::get_xpath_ctx(&document).ok()?;
+    let script = Util::evaluate_xpath(&context, xpath, false).ok()?;
+    script.first().map(|script| script.get_content())
+}
+
+fn parse_json(json: &str) -> Option<Value> {
+    serde_json::from_str(json)
+        .map_err(|error| log::debug!("Failed to parse embedded JSON: {error}"))
+        .ok()
+}
+
+/// Limit for the number of values resolved from a `devalue` payload. Values referenced
+/// several times are resolved again for every reference.
+const MAX_UNFLATTEN_NODES: usize = 100_000;
+
+/// Resolve the references of a payload serialized with `devalue`: every value is an index
+/// into the flat `values` array, negative indices stand for `undefined`, `NaN` and the like.
+/// Arrays starting with a string are special types such as `["Date", "2026-10-17"]` or
+/// wrappers of Vue like `["Reactive", 1]`.
+///
+/// References back to a value that is being resolved become `null`, as does everything
+/// beyond `MAX_DEPTH` or `MAX_UNFLATTEN_NODES`.
+fn unflatten(values: &[Value]) -> Value {
+    Unflatten {
+        values,
+        path: vec![false; values.len()],
+        budget: MAX_UNFLATTEN_NODES,
+    }
+    .resolve(&Value::from(0), 0)
+}
+
+struct Unflatten<'a> {
+    values: &'a [Value],
+    /// Indices of the values that are currently being resolved
+    path: Vec<bool>,
+    budget: usize,
+}
+
+impl Unflatten<'_> {
+    fn resolve(&mut self, index: &Value, depth: usize) -> Value {
+        let Some(index) = index
+            .as_u64()
+            .and_then(|index| usize::try_from(index).ok())
+            .filter(|&index| index < self.values.len() && !self.path[index])
+            .filter(|_| depth < MAX_DEPTH && self.budget > 0)
+        else {
+            return Value::Null;
+        };
+
+        self.budget -= 1;
+        self.path[index] = true;
+        let value = self.resolve_value(&self.values[index], depth);
+        self.path[index] = false;
+        value
+    }
+
+    fn resolve_value(&mut self, value: &Value, depth: usize) -> Value {
+        match value {
+            Value::Array(items) => match items.split_first() {
+                Some((Value::String(kind), items)) => match kind.as_str() {
+                    "Date" | "RegExp" | "BigInt" | "Object" => {
+                        items.first().cloned().unwrap_or_default()
+                    }
+                    "Set" => items
+                        .iter()
+                        .map(|item| self.resolve(item, depth + 1))
+                        .collect(),
+                    // object without prototype: alternating keys and indices
+                    "null" => items
+                        .chunks_exact(2)
+                        .filter_map(|pair| {
+                            let key = pair[0].as_str()?;
+                            Some((key.to_string(), self.resolve(&pair[1], depth + 1)))
+                        })
+                        .collect::<Map<_, _>>()
+                        .into(),
+                    // alternating indices of keys and values
+                    "Map" => items
+                        .chunks_exact(2)
+                        .filter_map(|pair| {
+                            let key = self.resolve(&pair[0], depth + 1);
+                            let key = key.as_str()?.to_string();
+                            Some((key, self.resolve(&pair[1], depth + 1)))
+                        })
+                        .collect::<Map<_, _>>()
+                        .into(),
+                    // Reactive, ShallowReactive, Ref, ...
+                    _ => items
+                        .first()
+                        .map(|item| self.resolve(item, depth + 1))
+                        .unwrap_or_default(),
+                },
+                _ => items
+                    .iter()
+                    .map(|item| self.resolve(item, depth + 1))
+                    .collect(),
+            },
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, item)| (key.clone(), self.resolve(item, depth + 1)))
+                .collect::<Map<_, _>>()
+                .into(),
+            value => value.clone(),
+        }
+    }
+}
+
+/// Article built from the object with the longest content in `data`. Only objects
+/// shaped like an article, with a title next to the content, are considered.
+fn article_from_json(data: &Value, url: &Url) -> Option<Article> {
+    let mut best = None;
+    find_article(data, 0, &mut best);
+    let (object, content) = best?;
+
+    let mut article = Article::new(url.clone());
+    article.title = string(object, TITLE_KEYS);
+    let authors = AUTHOR_KEYS
+        .iter()
+        .filter_map(|key| object.get(*key))
+        .map(authors)
+        .find(|authors| !authors.is_empty());
+    if let Some(authors) = authors {
+        article.set_authors(authors, MetadataSource::SiteExtractor);
+    }
+    article.date = string(object, DATE_KEYS).and_then(|date| date::parse_date(&date, None));
+    article.date_modified =
+        string(object, DATE_MODIFIED_KEYS).and_then(|date| date::parse_date(&date, None));
+    article.thumbnail_url = IMAGE_KEYS
+        .iter()
+        .filter_map(|key| object.get(*key))
+        .find_map(image_url)
+        .and_then(|image| url.join(&image).ok())
+        .map(String::from);
+    article.excerpt = string(object, EXCERPT_KEYS);
+    article.html = Some(content_html(content));
+    Some(article)
+}
+
+fn find_article<'a>(
+    value: &'a Value,
+    depth: usize,
+    best: &mut Option<(&'a Map<String, Value>, &'a str)>,
+) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                find_article(value, depth + 1, best);
+            }
+        }
+        Value::Object(object) => {
+            let content = CONTENT_KEYS
+                .iter()
+                .filter_map(|key| object.get(*key)?.as_str())
+                .find(|content| content.len() >= constants::DEFAULT_CHAR_THRESHOLD)
+                .filter(|_| string(object, TITLE_KEYS).is_some());
+            if let Some(content) = content {
+                if !best.is_some_and(|(_, best)| best.len() >= content.len()) {
+                    *best = Some((object, content));
+                }
+            }
+
+            for value in object.values() {
+                find_article(value, depth + 1, best);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn string(object: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| object.get(*key)?.as_str())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(Into::into)
+}
+
+/// `"name"`, `{ "name": "name", "url": "url" }` or a list of them
+fn authors(value: &Value) -> Vec<Author> {
+    match value {
+        Value::Array(values) => values.iter().flat_map(authors).collect(),
+        Value::Object(object) => string(object, &["name"])
+            .map(|name| Author {
+                name,
+                url: string(object, &["url"]),
+            })
+            .into_iter()
+            .collect(),
+        Value::String(name) if !name.trim().is_empty() => vec![Author::new(name.trim())],
+        _ => Vec::new(),
+    }
+}
+
+/// `"url"` or `{ "url": "url" }`
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(object) => string(object, &["url", "src"]),
+        Value::String(url) if !url.trim().is_empty() => Some(url.trim().into()),
+        _ => None,
+    }
+}
+
+/// Content without any markup is split into paragraphs at empty lines
+fn content_html(content: &str) -> String {
+    if content.contains('<') {
+        return content.into();
+    }
+
+    content
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>", escaper::encode_minimal(paragraph)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unflatten, MAX_UNFLATTEN_NODES};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn unflatten_devalue() {
+        let payload = json!([
+            ["ShallowReactive", 1],
+            { "data": 2, "state": 7 },
+            ["ShallowReactive", 3],
+            { "post": 4 },
+            { "title": 5, "date": 6, "tags": 8 },
+            "The title",
+            ["Date", "2026-10-17T08:30:00.000Z"],
+            ["null", "count", -1],
+            ["Set", 5]
+        ]);
+        let Value::Array(values) = payload else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            unflatten(&values),
+            json!({
+                "data": { "post": {
+                    "title": "The title",
+                    "date": "2026-10-17T08:30:00.000Z",
+                    "tags": ["The title"]
+                } },
+                "state": { "count": null }
+            })
+        );
+    }
+
+    #[test]
+    fn unflatten_shared_and_cyclic_references() {
+        assert_eq!(unflatten(&[json!([0, 0])]), json!([null, null]));
+        assert_eq!(
+            unflatten(&[json!({ "self": 0, "child": 1 }), json!({ "parent": 0 })]),
+            json!({ "self": null, "child": { "parent": null } })
+        );
+
+        // every value references the next one twice: 2^30 paths without a budget
+        let mut values: Vec<Value> = (1..30).map(|next| json!([next, next])).collect();
+        values.push(json!("leaf"));
+        let resolved = unflatten(&values);
+        let first_leaf = (0..29).fold(&resolved, |value, _| &value[0]);
+        assert_eq!(first_leaf, "leaf");
+        let leaves = resolved.to_string().matches("leaf").count();
+        assert!(leaves <= MAX_UNFLATTEN_NODES);
+    }
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+/// Result of checking the `test_url` entries of the site configs against
+/// saved HTML snapshots. See `FullTextParser::verify_site_configs`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SiteTestReport {
+    /// Site configs with at least one snapshot
+    pub sites: Vec<SiteTestResult>,
+    /// File names of the site configs with `test_url` entries but no snapshot at all.
+    /// They weren't checked and don't count as failed.
+    pub sites_without_snapshots: Vec<String>,
+}
+
+impl SiteTestReport {
+    /// Whether all checked sites passed, see `SiteTestResult::passed`
+    pub fn passed(&self) -> bool {
+        self.sites.iter().all(SiteTestResult::passed)
+    }
+
+    pub fn to_json(&self) -> String {
+        // serializing plain structs with string keys can't fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Test results of a single site config
+#[derive(Clone, Debug, Serialize)]
+pub struct SiteTestResult {
+    /// File name of the site config, e.g. `example.com.txt`
+    pub config: String,
+    pub tests: Vec<TestUrlResult>,
+    /// Test URLs without a snapshot in the snapshot directory
+    pub missing_snapshots: Vec<String>,
+}
+
+impl SiteTestResult {
+    /// Whether all test URLs passed. A missing snapshot fails the site, as its test URL
+    /// wasn't checked.
+    pub fn passed(&self) -> bool {
+        self.missing_snapshots.is_empty() && self.tests.iter().all(TestUrlResult::passed)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TestUrlResult {
+    pub url: String,
+    pub body_found: bool,
+    /// `test_contains` strings that are not part of the extracted article
+    pub missing_strings: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl TestUrlResult {
+    pub fn passed(&self) -> bool {
+        self.body_found && self.missing_strings.is_empty() && self.error.is_none()
+    }
+}
+
+/// File name the snapshot of `test_url` is expected under: all characters
+/// except ASCII letters, digits, `.` and `-` are replaced by `_` and the name is
+/// cut to 200 characters. A hash of the complete URL keeps the names of URLs apart
+/// that only differ in the replaced or cut characters.
+///
+/// `https://example.com/some/article` becomes `example.com_some_article-3432fb55.html`.
+pub fn snapshot_file_name(test_url: &str) -> String {
+    let url = test_url
+        .split_once("://")
+        .map(|(_scheme, rest)| rest)
+        .unwrap_or(test_url);
+
+    let mut name: String = url
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(200)
+        .collect();
+    name.push_str(&format!("-{:08x}.html", fnv1a(test_url.as_bytes()) as u32));
+    name
+}
+
+/// 64 bit FNV-1a, which unlike `DefaultHasher` is stable across Rust versions
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
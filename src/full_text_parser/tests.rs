@@ -0,0 +1,1240 @@
+use super::{
+    collect_trace,
+    config::{ConfigEntry, MoveInto, PageLink, WrapIn},
+    error::FullTextParserError,
+    snapshot_file_name, ExtractionOptions, FnPass, FullTextParser, PassContext, SiteExtractor,
+    TraceEvent,
+};
+use crate::article::{Article, Author, MetadataSource};
+use crate::util::Util;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Arc;
+use url::Url;
+
+/// Sidecar `metadata.json` of a golden test
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct GoldenMetadata {
+    /// Selects the embedded site config
+    url: String,
+    title: Option<String>,
+    author: Option<String>,
+    /// RFC 3339
+    date: Option<String>,
+}
+
+/// Extract `resources/tests/ftr/<name>/source.html` with the embedded site config
+/// of the url in `metadata.json` and compare the result against `expected.html`
+/// and the title, author and date in `metadata.json`.
+///
+/// Run `UPDATE_GOLDEN=1 cargo test` to regenerate `expected.html` and
+/// `metadata.json` after an intended change of the output.
+fn run_golden_test(name: &str) {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Debug)
+        .is_test(true)
+        .try_init();
+
+    let dir = format!("./resources/tests/ftr/{name}");
+    let html =
+        fs::read_to_string(format!("{dir}/source.html")).expect("Failed to read source HTML");
+    let metadata =
+        fs::read_to_string(format!("{dir}/metadata.json")).expect("Failed to read metadata.json");
+    let expected_metadata: GoldenMetadata =
+        serde_json::from_str(&metadata).expect("Failed to parse metadata.json");
+
+    let url = Url::parse(&expected_metadata.url).unwrap();
+    let article = FullTextParser::new(None)
+        .parse_offline(vec![html], None, Some(url))
+        .unwrap();
+    let html = article.html.unwrap_or_default();
+    let metadata = GoldenMetadata {
+        url: expected_metadata.url.clone(),
+        title: article.title,
+        author: article.author,
+        date: article.date.map(|date| date.to_rfc3339()),
+    };
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(format!("{dir}/expected.html"), &html).unwrap();
+        let metadata = serde_json::to_string_pretty(&metadata).unwrap();
+        fs::write(format!("{dir}/metadata.json"), metadata + "\n").unwrap();
+        return;
+    }
+
+    let expected =
+        fs::read_to_string(format!("{dir}/expected.html")).expect("Failed to read expected HTML");
+    assert_eq!(expected, html);
+    assert_eq!(expected_metadata, metadata);
+}
+
+#[test]
+fn golem() {
+    run_golden_test("golem")
+}
+
+#[test]
+fn hardwareluxx() {
+    run_golden_test("hardwareluxx")
+}
+
+#[test]
+fn heise_1() {
+    run_golden_test("heise-1")
+}
+
+#[test]
+fn phoronix() {
+    run_golden_test("phoronix")
+}
+
+#[test]
+fn spiegel_1() {
+    run_golden_test("spiegel-1")
+}
+
+#[test]
+fn youtube() {
+    run_golden_test("youtube")
+}
+
+fn page(content: &str, next: Option<&str>) -> String {
+    let next = next
+        .map(|next| format!(r#"<a rel="next" href="{next}">next</a>"#))
+        .unwrap_or_default();
+    format!(
+        r#"<html><body><div id="content"><p>{content}</p></div><div class="pager">{next}</div></body></html>"#
+    )
+}
+
+#[test]
+fn follow_next_page_link() {
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        next_page_link: vec![PageLink::new("//a[@rel='next']/@href")],
+        ..Default::default()
+    };
+    let url = Url::parse("https://example.com/article").unwrap();
+    let parser = FullTextParser::new(None);
+
+    let mut requested = Vec::new();
+    let mut provider = |url: &Url| {
+        requested.push(url.to_string());
+        match url.path() {
+            "/article/2" => Some(page("second page", Some("/article/3"))),
+            // link back to the first page to provoke a loop
+            "/article/3" => Some(page("third page", Some("/article"))),
+            _ => None,
+        }
+    };
+
+    let article = parser
+        .parse_with_provider(
+            &page("first page", Some("/article/2")),
+            Some(&config),
+            Some(url),
+            &mut provider,
+        )
+        .unwrap();
+
+    assert_eq!(
+        requested,
+        vec![
+            "https://example.com/article/2",
+            "https://example.com/article/3"
+        ]
+    );
+
+    let html = article.html.unwrap();
+    assert!(html.contains("first page"));
+    assert!(html.contains("second page"));
+    assert!(html.contains("third page"));
+}
+
+#[test]
+fn follow_single_page_link() {
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        single_page_link: vec![PageLink::new("//a[@class='print']")],
+        next_page_link: vec![PageLink::new("//a[@rel='next']/@href")],
+        ..Default::default()
+    };
+    let url = Url::parse("https://example.com/article").unwrap();
+    let parser = FullTextParser::new(None);
+
+    let first_page = page("first page", Some("/article/2")).replace(
+        "</body>",
+        r#"<a class="print" href="print">print</a></body>"#,
+    );
+    let single_page_url = parser
+        .single_page_url(&first_page, Some(&config), &url)
+        .unwrap();
+    assert_eq!(
+        single_page_url.as_ref().map(Url::as_str),
+        Some("https://example.com/print")
+    );
+
+    let mut provider = |url: &Url| match url.path() {
+        "/print" => Some(page("complete article", None)),
+        _ => None,
+    };
+    let article = parser
+        .parse_with_provider(&first_page, Some(&config), Some(url), &mut provider)
+        .unwrap();
+
+    let html = article.html.unwrap();
+    assert!(html.contains("complete article"));
+    assert!(!html.contains("first page"));
+}
+
+#[test]
+fn conditional_single_page_link() {
+    let config = ConfigEntry {
+        single_page_link: vec![
+            PageLink {
+                xpath: "//a[@class='print']".into(),
+                condition: Some("//div[@class='paywall']".into()),
+            },
+            PageLink {
+                xpath: "//a[@class='amp']".into(),
+                condition: Some("//div[@class='pager']".into()),
+            },
+        ],
+        ..Default::default()
+    };
+    let url = Url::parse("https://example.com/article").unwrap();
+    let parser = FullTextParser::new(None);
+
+    let html = page("first page", None).replace(
+        "</body>",
+        r#"<a class="print" href="print">print</a><a class="amp" href="amp">amp</a></body>"#,
+    );
+    let single_page_url = parser.single_page_url(&html, Some(&config), &url).unwrap();
+    assert_eq!(
+        single_page_url.as_ref().map(Url::as_str),
+        Some("https://example.com/amp")
+    );
+}
+
+#[test]
+fn request_profile() {
+    let parser = FullTextParser::new(None);
+    let url = Url::parse("https://www.echo-online.de/some/article").unwrap();
+    let profile = parser.request_profile(&url);
+
+    assert!(profile
+        .headers
+        .iter()
+        .any(|header| header.name == "referer" && header.value == "https://www.echo-online.de/"));
+}
+
+#[test]
+fn restructure_before_extraction() {
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        wrap_in: vec![WrapIn {
+            tag: "blockquote".into(),
+            xpath: "//div[@class='quote']".into(),
+        }],
+        dissolve: vec!["//div[@class='layout']".into()],
+        move_into: vec![MoveInto {
+            target: "//div[@id='content']".into(),
+            xpath: "//p[@id='moved']".into(),
+        }],
+        ..Default::default()
+    };
+    let html = r#"<html><body>
+        <div id="content">
+            <div class="layout"><p>Some paragraph of the article that is long enough to be kept around.</p></div>
+            <div class="quote">A quote that is part of the article as well.</div>
+        </div>
+        <p id="moved">Moved paragraph</p>
+    </body></html>"#;
+
+    let parser = FullTextParser::new(None);
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    let html = article.html.unwrap();
+
+    assert!(!html.contains("layout"));
+    assert!(html.contains("<blockquote>"));
+    assert!(html.contains("Moved paragraph"));
+}
+
+#[test]
+fn strip_attr_and_lazy_load_attr() {
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        strip_attr: vec!["//p/@style".into()],
+        src_lazy_load_attr: Some("data-real-src".into()),
+        ..Default::default()
+    };
+    let html = r#"<html><body>
+        <div id="content">
+            <p style="color: red">Some paragraph of the article that is long enough to be kept around.</p>
+            <img src="placeholder.gif" data-src="https://example.com/wrong.jpg" data-real-src="https://example.com/real.jpg">
+        </div>
+    </body></html>"#;
+
+    let parser = FullTextParser::new(None);
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    let html = article.html.unwrap();
+
+    assert!(!html.contains("color: red"));
+    assert!(html.contains(r#"src="https://example.com/real.jpg""#));
+}
+
+#[test]
+fn verify_site_configs() {
+    let dir = std::env::temp_dir().join(format!(
+        "article-extractor-verify-site-configs-{}",
+        std::process::id()
+    ));
+    let config_dir = dir.join("config");
+    let snapshot_dir = dir.join("snapshots");
+    _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::create_dir_all(&snapshot_dir).unwrap();
+
+    fs::write(
+        config_dir.join("verify.example.com.txt"),
+        r#"
+body: //div[@id='content']
+test_url: https://verify.example.com/article
+test_contains: paragraph of the article
+test_contains: not part of the snapshot
+test_url: https://verify.example.com/missing
+"#,
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("fallback.example.com.txt"),
+        r#"
+body: //div[@id='renamed']
+test_url: https://fallback.example.com/article
+"#,
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("unsaved.example.com.txt"),
+        "test_url: https://unsaved.example.com/article",
+    )
+    .unwrap();
+    fs::write(
+        snapshot_dir.join(snapshot_file_name("https://verify.example.com/article")),
+        page(
+            "Some paragraph of the article that is long enough to be kept around.",
+            None,
+        ),
+    )
+    .unwrap();
+    fs::write(
+        snapshot_dir.join(snapshot_file_name("https://fallback.example.com/article")),
+        page(
+            &"A paragraph of the article, long enough to be kept. ".repeat(20),
+            None,
+        ),
+    )
+    .unwrap();
+
+    let parser = FullTextParser::new(Some(&config_dir));
+    let report = parser.verify_site_configs(&snapshot_dir, None);
+    let fallback_report =
+        parser.verify_site_configs(&snapshot_dir, Some(&["fallback.example.com.txt"]));
+    _ = fs::remove_dir_all(&dir);
+
+    assert!(!report.passed());
+    let site = report
+        .sites
+        .iter()
+        .find(|site| site.config == "verify.example.com.txt")
+        .unwrap();
+    assert_eq!(site.tests.len(), 1);
+    assert!(site.tests[0].body_found);
+    assert_eq!(
+        site.tests[0].missing_strings,
+        vec!["not part of the snapshot"]
+    );
+    assert_eq!(
+        site.missing_snapshots,
+        vec!["https://verify.example.com/missing"]
+    );
+    assert!(report.to_json().contains("\"body_found\": true"));
+
+    // the content was found by Readability, not by the body rule of the config
+    let site = report
+        .sites
+        .iter()
+        .find(|site| site.config == "fallback.example.com.txt")
+        .unwrap();
+    assert!(!site.tests[0].body_found);
+    assert!(site.tests[0].error.is_none());
+
+    // sites without any snapshot are reported apart from the checked ones
+    assert!(!report
+        .sites
+        .iter()
+        .any(|site| site.config == "unsaved.example.com.txt"));
+    assert!(report
+        .sites_without_snapshots
+        .contains(&"unsaved.example.com.txt".to_string()));
+
+    assert_eq!(fallback_report.sites.len(), 1);
+    assert_eq!(fallback_report.sites[0].config, "fallback.example.com.txt");
+    assert!(fallback_report.sites_without_snapshots.is_empty());
+}
+
+#[test]
+fn distinct_snapshot_file_names() {
+    assert_ne!(
+        snapshot_file_name("https://example.com/article?id=1"),
+        snapshot_file_name("https://example.com/article/id=1")
+    );
+
+    let long_url = format!("https://example.com/{}", "a".repeat(300));
+    assert_ne!(
+        snapshot_file_name(&format!("{long_url}/1")),
+        snapshot_file_name(&format!("{long_url}/2"))
+    );
+    assert!(snapshot_file_name("https://example.com/some/article")
+        .starts_with("example.com_some_article-"));
+}
+
+#[test]
+fn wildcard_config_lookup() {
+    assert_eq!(
+        FullTextParser::config_file_names("a.someone.dreamwidth.org"),
+        vec![
+            "a.someone.dreamwidth.org.txt",
+            ".someone.dreamwidth.org.txt",
+            ".dreamwidth.org.txt"
+        ]
+    );
+
+    let parser = FullTextParser::new(None);
+    let url = Url::parse("https://someone.dreamwidth.org/1234.html").unwrap();
+    let config = parser.get_grabber_config(&url).unwrap();
+    assert!(std::ptr::eq(
+        config,
+        parser.config_files.get(".dreamwidth.org.txt").unwrap()
+    ));
+}
+
+#[test]
+fn autodetect_on_failure_disables_global_config() {
+    let parser = FullTextParser::new(None);
+    let global_config = parser.get_global_config(None).unwrap();
+    assert!(!global_config.xpath_strip.is_empty());
+
+    let config = ConfigEntry {
+        autodetect_on_failure: Some(false),
+        ..Default::default()
+    };
+    let global_config = parser.get_global_config(Some(&config)).unwrap();
+    assert!(global_config.xpath_strip.is_empty());
+}
+
+#[test]
+fn prune_no_keeps_body_intact() {
+    let html = r#"<html><body>
+        <div id="content">
+            <p>Some paragraph of the article that is long enough to be kept around.</p>
+            <div class="links"><a href="/a">First link</a> <a href="/b">Second link</a></div>
+        </div>
+    </body></html>"#;
+    let mut config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    assert!(!article.html.unwrap().contains("Second link"));
+
+    config.prune = Some(false);
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    assert!(article.html.unwrap().contains("Second link"));
+}
+
+#[test]
+fn autodetect_on_failure_no() {
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='missing']".into()],
+        autodetect_on_failure: Some(false),
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+    let html = page(
+        "Some paragraph of the article that is long enough to be kept around.",
+        None,
+    );
+
+    let result = parser.parse_offline(vec![html], Some(&config), None);
+    assert!(matches!(
+        result,
+        Err(FullTextParserError::AutodetectDisabled)
+    ));
+}
+
+#[test]
+fn readability_byline_as_author() {
+    let paragraph = "<p>This is a paragraph of the article, with enough text and commas, to be scored by readability.</p>";
+    let html = format!(
+        r#"<html><body><article><div class="byline">By Jane Doe</div>{}</article></body></html>"#,
+        paragraph.repeat(10)
+    );
+
+    let parser = FullTextParser::new(None);
+    let article = parser
+        .parse_offline(vec![html], Some(&ConfigEntry::default()), None)
+        .unwrap();
+
+    assert_eq!(article.author.as_deref(), Some("By Jane Doe"));
+    assert_eq!(article.author_source, Some(MetadataSource::Byline));
+}
+
+#[test]
+fn json_ld_metadata() {
+    let html = r#"<html><head>
+        <title>Page title | Example</title>
+        <script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "NewsArticle", "headline": "JSON-LD headline",
+             "author": {"@type": "Person", "name": "Jane Doe"}, "datePublished": "2023-03-01T10:00:00Z"}
+        </script>
+    </head><body><div id="content"><p>Some paragraph of the article that is long enough to be kept around.</p></div></body></html>"#;
+    let mut config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    assert_eq!(article.title.as_deref(), Some("JSON-LD headline"));
+    assert_eq!(article.author.as_deref(), Some("Jane Doe"));
+    assert_eq!(article.author_source, Some(MetadataSource::JsonLd));
+    assert_eq!(
+        article.date.map(|date| date.to_rfc3339()).as_deref(),
+        Some("2023-03-01T10:00:00+00:00")
+    );
+
+    config.skip_json_ld = true;
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    assert_ne!(article.title.as_deref(), Some("JSON-LD headline"));
+    assert_ne!(article.author_source, Some(MetadataSource::JsonLd));
+}
+
+#[test]
+fn date_formats_and_fallbacks() {
+    let body = r#"<div id="content"><p>Some paragraph of the article that is long enough to be kept around.</p></div>"#;
+    let parser = FullTextParser::new(None);
+    let date = |head: &str, config: &ConfigEntry| {
+        let html = format!("<html><head>{head}</head><body>{body}</body></html>");
+        parser
+            .parse_offline(vec![html], Some(config), None)
+            .unwrap()
+            .date
+            .map(|date| date.to_rfc3339())
+    };
+
+    let mut config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    assert_eq!(
+        date(
+            r#"<meta property="article:published_time" content="October 17, 2026">"#,
+            &config
+        )
+        .as_deref(),
+        Some("2026-10-17T00:00:00+00:00")
+    );
+    assert_eq!(
+        date(
+            r#"<meta name="DC.date" content="17.10.2026 10:30">"#,
+            &config
+        )
+        .as_deref(),
+        Some("2026-10-17T10:30:00+00:00")
+    );
+
+    config.timezone = Some("+02:00".into());
+    assert_eq!(
+        date(
+            r#"<meta name="DC.date" content="17.10.2026 10:30">"#,
+            &config
+        )
+        .as_deref(),
+        Some("2026-10-17T08:30:00+00:00")
+    );
+
+    config.xpath_date = vec!["//span[@class='date']".into()];
+    let body_with_date = r#"<span class="date">17. Oktober 2026, 10:30</span>"#;
+    let html = format!(
+        "<html><body>{body_with_date}<div id=\"content\"><p>Some paragraph of the article that is long enough to be kept around.</p></div></body></html>"
+    );
+    let article = parser
+        .parse_offline(vec![html], Some(&config), None)
+        .unwrap();
+    assert_eq!(
+        article.date.map(|date| date.to_rfc3339()).as_deref(),
+        Some("2026-10-17T08:30:00+00:00")
+    );
+}
+
+#[test]
+fn multiple_authors_and_dates() {
+    let html = r#"<html><head>
+        <meta property="article:published_time" content="2026-10-16T10:00:00Z">
+        <meta property="article:modified_time" content="2026-10-17T08:30:00Z">
+    </head><body>
+        <div class="byline">
+            <a class="author" href="/authors/jane">Jane  Doe</a> and
+            <span class="author">John Doe</span>
+        </div>
+        <div id="content"><p>Some paragraph of the article that is long enough to be kept around.</p></div>
+    </body></html>"#;
+    let config = ConfigEntry {
+        xpath_author: vec!["//*[@class='author']".into()],
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let url = Url::parse("https://example.com/article").unwrap();
+    let parser = FullTextParser::new(None);
+
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), Some(url))
+        .unwrap();
+    assert_eq!(
+        article.authors,
+        vec![
+            Author {
+                name: "Jane Doe".into(),
+                url: Some("https://example.com/authors/jane".into()),
+            },
+            Author::new("John Doe"),
+        ]
+    );
+    assert_eq!(article.author.as_deref(), Some("Jane Doe, John Doe"));
+    assert_eq!(article.author_source, Some(MetadataSource::SiteConfig));
+    assert_eq!(
+        article.date.map(|date| date.to_rfc3339()).as_deref(),
+        Some("2026-10-16T10:00:00+00:00")
+    );
+    assert_eq!(
+        article
+            .date_modified
+            .map(|date| date.to_rfc3339())
+            .as_deref(),
+        Some("2026-10-17T08:30:00+00:00")
+    );
+}
+
+#[test]
+fn microdata_authors() {
+    let html = r#"<html><body>
+        <span itemprop="author" itemscope itemtype="https://schema.org/Person">
+            <a itemprop="url" href="https://example.com/jane"><span itemprop="name">Jane Doe</span></a>
+        </span>
+        <meta itemprop="author" content="John Doe">
+        <time itemprop="dateModified" datetime="2026-10-17">Oct 17</time>
+        <div id="content"><p>Some paragraph of the article that is long enough to be kept around.</p></div>
+    </body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    assert_eq!(
+        article.authors,
+        vec![
+            Author {
+                name: "Jane Doe".into(),
+                url: Some("https://example.com/jane".into()),
+            },
+            Author::new("John Doe"),
+        ]
+    );
+    assert_eq!(article.author_source, Some(MetadataSource::Meta));
+    assert_eq!(
+        article
+            .date_modified
+            .map(|date| date.to_rfc3339())
+            .as_deref(),
+        Some("2026-10-17T00:00:00+00:00")
+    );
+}
+
+#[test]
+fn base_element_for_relative_urls() {
+    let html = r#"<html><head><base href="https://cdn.example.com/articles/2026/"></head><body>
+        <div id="content">
+            <p>Some paragraph of the article that is long enough to be kept around.</p>
+            <img src="images/photo.jpg" srcset="images/photo-2x.jpg 2x">
+            <a href="../2025/older.html">Older article</a>
+        </div>
+    </body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let url = Url::parse("https://example.com/news/article").unwrap();
+    let parser = FullTextParser::new(None);
+
+    let html = parser
+        .parse_offline(vec![html.into()], Some(&config), Some(url))
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(html.contains(r#"src="https://cdn.example.com/articles/2026/images/photo.jpg""#));
+    assert!(html.contains("https://cdn.example.com/articles/2026/images/photo-2x.jpg 2x"));
+    assert!(html.contains(r#"href="https://cdn.example.com/articles/2025/older.html""#));
+}
+
+#[test]
+fn repair_media_urls() {
+    let html = r#"<html><body><div id="content">
+        <p>Some paragraph of the article that is long enough to be kept around.</p>
+        <picture>
+            <source srcset="img/photo.webp 1x, img/photo-2x.webp 2x" type="image/webp">
+            <img src="img/photo.jpg">
+        </picture>
+        <video src="media/clip.mp4" poster="media/poster.jpg">
+            <track src="media/clip.vtt" kind="captions">
+        </video>
+        <audio src="/media/podcast.mp3">Listen to the podcast</audio>
+        <blockquote cite="sources/quote.html">A quote long enough to be part of the article.</blockquote>
+        <p><q cite="javascript:void(0)">Short quote</q></p>
+    </div></body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let url = Url::parse("https://example.com/news/article").unwrap();
+    let parser = FullTextParser::new(None);
+
+    let html = parser
+        .parse_offline(vec![html.into()], Some(&config), Some(url))
+        .unwrap()
+        .html
+        .unwrap();
+    for expected in [
+        r#"srcset="https://example.com/news/img/photo.webp 1x, https://example.com/news/img/photo-2x.webp 2x""#,
+        r#"src="https://example.com/news/img/photo.jpg""#,
+        r#"src="https://example.com/news/media/clip.mp4""#,
+        r#"poster="https://example.com/news/media/poster.jpg""#,
+        r#"src="https://example.com/news/media/clip.vtt""#,
+        r#"src="https://example.com/media/podcast.mp3""#,
+        r#"cite="https://example.com/news/sources/quote.html""#,
+        "<q>Short quote</q>",
+    ] {
+        assert!(html.contains(expected), "missing '{expected}' in {html}");
+    }
+}
+
+#[test]
+fn allowed_video_embeds() {
+    let source = r#"<html><body><div id="content">
+        <p>Some paragraph of the article that is long enough to be kept around.</p>
+        <div><iframe src="https://www.youtube.com/embed/abc"></iframe></div>
+        <div><iframe src="https://peertube.example.org/videos/embed/abc"></iframe></div>
+        <div><iframe src="https://videos.example.com/player/abc"></iframe></div>
+        <div><iframe src="https://ads.example.com/banner"></iframe></div>
+    </div></body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let mut parser = FullTextParser::new(None);
+
+    let html = parser
+        .parse_offline(vec![source.into()], Some(&config), None)
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(html.contains(r#"src="https://www.youtube.com/embed/abc" width="480" height="360""#));
+    assert!(html.contains("https://peertube.example.org/videos/embed/abc"));
+    assert!(!html.contains("https://videos.example.com/player/abc"));
+    assert!(!html.contains("https://ads.example.com/banner"));
+    assert!(!html.contains("is_allowed_video"));
+
+    parser.set_allowed_video_regex(regex::Regex::new(r"//videos\.example\.com/").unwrap());
+    let html = parser
+        .parse_offline(vec![source.into()], Some(&config), None)
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(html.contains("https://videos.example.com/player/abc"));
+    assert!(!html.contains("https://www.youtube.com/embed/abc"));
+}
+
+#[test]
+fn strip_rules_keep_allowed_embeds() {
+    let source = r#"<html><body><div id="content">
+        <p>Some paragraph of the article that is long enough to be kept around.</p>
+        <p>Another paragraph of the article, with a video below it, that is kept.</p>
+        <iframe src="https://videos.example.com/player/abc"></iframe>
+        <iframe src="https://ads.example.com/banner"></iframe>
+    </div></body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        xpath_strip: vec!["//iframe".into()],
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+
+    let options = ExtractionOptions {
+        allowed_video: regex::Regex::new(r"//videos\.example\.com/").unwrap(),
+        ..Default::default()
+    };
+    let html = parser
+        .parse_offline_with_options(vec![source.into()], Some(&config), None, &options)
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(html.contains("https://videos.example.com/player/abc"));
+    assert!(!html.contains("https://ads.example.com/banner"));
+
+    let options = ExtractionOptions {
+        strip_embeds: false,
+        ..Default::default()
+    };
+    let html = parser
+        .parse_offline_with_options(vec![source.into()], Some(&config), None, &options)
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(html.contains("https://videos.example.com/player/abc"));
+    assert!(html.contains("https://ads.example.com/banner"));
+}
+
+#[test]
+fn extraction_options() {
+    let source = r#"<html><body><div id="content">
+        <h1>Getting started</h1>
+        <p style="color: red">Some paragraph of the documentation that is long enough to be kept.</p>
+        <aside><p>Note: the aside of the documentation has to be kept as well.</p></aside>
+        <footer><p>Last updated on the 17th of October.</p></footer>
+    </div></body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+
+    let html = parser
+        .parse_offline(vec![source.into()], Some(&config), None)
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(!html.contains("<aside"));
+    assert!(!html.contains("<footer"));
+    assert!(!html.contains("style="));
+
+    let options = ExtractionOptions {
+        strip_asides: false,
+        strip_footers: false,
+        strip_styles: false,
+        h1_to_h2: false,
+        ..Default::default()
+    };
+    let html = parser
+        .parse_offline_with_options(vec![source.into()], Some(&config), None, &options)
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(html.contains("<h1>Getting started</h1>"));
+    assert!(html.contains("the aside of the documentation"));
+    assert!(html.contains("<footer"));
+    assert!(html.contains(r#"style="color: red""#));
+}
+
+#[test]
+fn custom_passes() {
+    let source = r#"<html><body><div id="content">
+        <p>Some paragraph of the documentation that is long enough to be kept.</p>
+        <details><summary>Details</summary><p>Hidden by default.</p></details>
+        <aside><p>Note: the aside of the documentation has to be kept as well.</p></aside>
+    </div></body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let mut options = ExtractionOptions::default();
+    assert_eq!(options.prepare_passes.names().first(), Some(&"h1_to_h2"));
+    assert!(options.prepare_passes.remove("strip_asides").is_some());
+    assert!(options.prepare_passes.remove("does_not_exist").is_none());
+
+    let open_details = FnPass::new("open_details", |ctx: &PassContext| {
+        for mut node in Util::evaluate_xpath(ctx.context, "//details", false)? {
+            _ = node.set_attribute("open", "open");
+        }
+        Ok(())
+    });
+    assert!(options
+        .prepare_passes
+        .insert_after("strip_forms", Arc::new(open_details)));
+    assert!(!options.page_passes.insert_before(
+        "does_not_exist",
+        Arc::new(FnPass::new("noop", |_: &PassContext| Ok(())))
+    ));
+    // a failing pass must not skip the ones after it
+    assert!(options.prepare_passes.insert_before(
+        "open_details",
+        Arc::new(FnPass::new("fail", |_: &PassContext| {
+            Err(FullTextParserError::Unknown)
+        }))
+    ));
+
+    let html = FullTextParser::new(None)
+        .parse_offline_with_options(vec![source.into()], Some(&config), None, &options)
+        .unwrap()
+        .html
+        .unwrap();
+    assert!(html.contains(r#"<details open="open">"#));
+    assert!(html.contains("the aside of the documentation"));
+}
+
+#[test]
+fn next_data_site_extractor() {
+    let content = "<p>The article is only part of the JSON data of the page and gets rendered by \
+        JavaScript in the browser. <a href=\\\"/docs/intro\\\">Read the introduction</a> first.</p>"
+        .repeat(5);
+    let source = format!(
+        r#"<html><head><meta property="og:site_name" content="Example Docs"></head><body>
+        <div id="__next"><p>Loading...</p></div>
+        <script id="__NEXT_DATA__" type="application/json">{{
+            "props": {{ "pageProps": {{ "post": {{
+                "title": "Rendered on the client",
+                "author": {{ "name": "Jane Doe", "url": "https://example.com/jane" }},
+                "publishedAt": "2026-10-17T08:30:00Z",
+                "content": "{content}"
+            }} }} }},
+            "page": "/blog/[slug]"
+        }}</script>
+    </body></html>"#
+    );
+    let url = Url::parse("https://example.com/blog/rendered").unwrap();
+    let mut parser = FullTextParser::new(None);
+
+    let article = parser
+        .parse_offline(vec![source.clone()], None, Some(url.clone()))
+        .unwrap();
+    assert_eq!(article.title.as_deref(), Some("Rendered on the client"));
+    assert_eq!(
+        article.authors,
+        vec![Author {
+            name: "Jane Doe".into(),
+            url: Some("https://example.com/jane".into()),
+        }]
+    );
+    assert_eq!(article.author_source, Some(MetadataSource::SiteExtractor));
+    assert_eq!(
+        article.date.map(|date| date.to_rfc3339()),
+        Some("2026-10-17T08:30:00+00:00".into())
+    );
+    assert_eq!(article.site_name.as_deref(), Some("Example Docs"));
+    let html = article.html.unwrap();
+    assert!(html.contains(r#"href="https://example.com/docs/intro""#));
+    assert!(!html.contains("Loading..."));
+
+    // generic extractors don't override a site config
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='__next']".into()],
+        ..Default::default()
+    };
+    let article = parser
+        .parse_offline(vec![source.clone()], Some(&config), Some(url.clone()))
+        .unwrap();
+    assert!(article.html.unwrap().contains("Loading..."));
+
+    assert!(parser.remove_site_extractor("next_data"));
+    assert!(!parser.remove_site_extractor("next_data"));
+    let article = parser.parse_offline(vec![source], None, Some(url)).unwrap();
+    assert_ne!(article.title.as_deref(), Some("Rendered on the client"));
+}
+
+#[test]
+fn empty_site_extractor_content() {
+    let content = "<form><p>Subscribe to the newsletter to never miss an article. \
+        Enter your email address below.</p></form>"
+        .repeat(6);
+    let article = "<p>The article of the page, rendered on the server as well.</p>".repeat(10);
+    let source = format!(
+        r#"<html><body><div id="__next"><article>{article}</article></div>
+        <script id="__NEXT_DATA__" type="application/json">{{
+            "props": {{ "pageProps": {{ "post": {{
+                "title": "Only a form",
+                "content": "{content}"
+            }} }} }}
+        }}</script>
+    </body></html>"#
+    );
+
+    let article = FullTextParser::new(None)
+        .parse_offline(vec![source], None, None)
+        .unwrap();
+    assert_ne!(article.title.as_deref(), Some("Only a form"));
+    assert!(article
+        .html
+        .unwrap()
+        .contains("The article of the page, rendered on the server as well."));
+}
+
+#[test]
+fn site_extractor_needs_article_shaped_object() {
+    let terms =
+        "By using this site you agree to the terms of service and the privacy policy. ".repeat(10);
+    let article = "<p>The article of the page, rendered on the server as well.</p>".repeat(10);
+    let source = format!(
+        r#"<html><body><div id="__next"><article>{article}</article></div>
+        <script id="__NEXT_DATA__" type="application/json">{{
+            "props": {{ "pageProps": {{ "legal": {{ "body": "{terms}" }} }} }}
+        }}</script>
+    </body></html>"#
+    );
+
+    let (article, trace) =
+        collect_trace(|| FullTextParser::new(None).parse_offline(vec![source], None, None));
+    let html = article.unwrap().html.unwrap();
+    assert!(html.contains("The article of the page, rendered on the server as well."));
+    assert!(!html.contains("terms of service"));
+    assert!(!trace
+        .iter()
+        .any(|event| matches!(event, TraceEvent::SiteExtractor { .. })));
+}
+
+#[test]
+fn nuxt_data_site_extractor() {
+    let content = "The article is only part of the state of the page.\\n\\n".repeat(12);
+    let source = format!(
+        r#"<html><body><div id="__nuxt"></div>
+        <script type="application/json" id="__NUXT_DATA__" data-ssr="true">[
+            ["ShallowReactive", 1], {{ "data": 2 }}, ["ShallowReactive", 3], {{ "article": 4 }},
+            {{ "headline": 5, "body": 6, "date": 7 }},
+            "Served by Nuxt", "{content}", ["Date", "2026-10-17T08:30:00.000Z"]
+        ]</script>
+    </body></html>"#
+    );
+
+    let article = FullTextParser::new(None)
+        .parse_offline(vec![source], None, None)
+        .unwrap();
+    assert_eq!(article.title.as_deref(), Some("Served by Nuxt"));
+    assert!(article.date.is_some());
+    let html = article.html.unwrap();
+    assert_eq!(html.matches("<p>").count(), 12);
+}
+
+#[test]
+fn custom_site_extractor() {
+    struct Example;
+
+    impl SiteExtractor for Example {
+        fn name(&self) -> &str {
+            "example"
+        }
+
+        fn matches(&self, url: &Url) -> bool {
+            url.host_str() == Some("example.com")
+        }
+
+        fn extract(&self, html: &str, url: &Url) -> Option<Article> {
+            let (_, text) = html.split_once("<!-- article: ")?;
+            let (text, _) = text.split_once(" -->")?;
+            let mut article = Article::new(url.clone());
+            article.title = Some("From a comment".into());
+            article.html = Some(format!("<p>{text}</p>"));
+            Some(article)
+        }
+    }
+
+    let source = r#"<html><head><title>Page title</title></head><body>
+        <!-- article: The whole article is hidden in a comment. -->
+        <div id="content"><p>Some paragraph that the site config would extract.</p></div>
+    </body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let mut parser = FullTextParser::new(None);
+    parser.register_site_extractor(Example);
+
+    let url = Url::parse("https://example.com/article").unwrap();
+    let article = parser
+        .parse_offline(vec![source.into()], Some(&config), Some(url))
+        .unwrap();
+    assert_eq!(article.title.as_deref(), Some("From a comment"));
+    assert!(article
+        .html
+        .unwrap()
+        .contains("The whole article is hidden in a comment."));
+
+    let url = Url::parse("https://example.org/article").unwrap();
+    let article = parser
+        .parse_offline(vec![source.into()], Some(&config), Some(url))
+        .unwrap();
+    assert_eq!(article.title.as_deref(), Some("Page title"));
+}
+
+#[test]
+fn trace_site_config_rules() {
+    let source = r#"<html><body><div id="content">
+        <p>Some paragraph of the article that is long enough to be kept around.</p>
+        <div class="ad"><p>Buy now</p></div>
+        <div class="promo-box"><p>Subscribe</p></div>
+    </div></body></html>"#;
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='missing']".into(), "//div[@id='content']".into()],
+        xpath_strip: vec!["//div[@class='ad']".into()],
+        strip_id_or_class: vec!["promo".into()],
+        strip_image_src: vec!["it's".into()],
+        wrap_in: vec![WrapIn {
+            tag: "blockquote".into(),
+            xpath: "//div[@id='content']/p".into(),
+        }],
+        move_into: vec![MoveInto {
+            target: "//div[@id='missing']".into(),
+            xpath: "//p".into(),
+        }],
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+
+    let (article, events) =
+        collect_trace(|| parser.parse_offline(vec![source.into()], Some(&config), None));
+    assert!(!article.unwrap().html.unwrap().contains("Buy now"));
+    for event in [
+        TraceEvent::Strip {
+            directive: "strip",
+            rule: "//div[@class='ad']".into(),
+            nodes: 1,
+        },
+        TraceEvent::Strip {
+            directive: "strip_id_or_class",
+            rule: "promo".into(),
+            nodes: 1,
+        },
+        TraceEvent::Strip {
+            directive: "strip_image_src",
+            rule: "it's".into(),
+            nodes: 0,
+        },
+        TraceEvent::Restructure {
+            directive: "wrap_in",
+            rule: "//div[@id='content']/p".into(),
+            nodes: 1,
+        },
+        TraceEvent::Restructure {
+            directive: "move_into",
+            rule: "//p".into(),
+            nodes: 0,
+        },
+        TraceEvent::Body {
+            rule: "//div[@id='missing']".into(),
+            nodes: 0,
+        },
+        TraceEvent::Body {
+            rule: "//div[@id='content']".into(),
+            nodes: 1,
+        },
+    ] {
+        assert!(events.contains(&event), "{event:?} missing in {events:?}");
+    }
+    assert!(!events.contains(&TraceEvent::ReadabilityFallback));
+}
+
+#[test]
+fn trace_readability_fallback() {
+    let source = format!(
+        "<html><body><article><p>{}</p><p>{}</p></article></body></html>",
+        "Some paragraph of the article, that is long enough, to be kept. ".repeat(10),
+        "Another paragraph of the article, that is long enough, to be kept. ".repeat(10)
+    );
+    let url = Url::parse("https://unknown.example.com/article").unwrap();
+    let parser = FullTextParser::new(None);
+
+    let (article, events) = collect_trace(|| parser.parse_offline(vec![source], None, Some(url)));
+    assert!(article.is_ok());
+    // the site config is looked up once per article
+    let no_site_config = TraceEvent::NoSiteConfig {
+        host: "unknown.example.com".into(),
+    };
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| **event == no_site_config)
+            .count(),
+        1
+    );
+    let fallback = events
+        .iter()
+        .position(|event| event == &TraceEvent::ReadabilityFallback)
+        .unwrap();
+    assert!(matches!(
+        events[fallback + 1..].last(),
+        Some(TraceEvent::ReadabilityAttempt {
+            strip_unlikely: true,
+            accepted: true,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn parse_offline_bytes() {
+    let html = r#"<html><head><meta charset="Shift_JIS"><title>日本語の記事</title></head><body>
+        <div id="content"><p style="color: red">これは文字コードの判定を確認するための十分に長い段落です。</p></div>
+    </body></html>"#;
+    let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(html);
+    let config = ConfigEntry {
+        xpath_body: vec!["//div[@id='content']".into()],
+        ..Default::default()
+    };
+    let parser = FullTextParser::new(None);
+
+    let article = parser
+        .parse_offline_bytes(vec![bytes.to_vec()], None, Some(&config), None)
+        .unwrap();
+    assert_eq!(article.encoding.as_deref(), Some("Shift_JIS"));
+    assert_eq!(article.title.as_deref(), Some("日本語の記事"));
+    assert!(article
+        .html
+        .unwrap()
+        .contains("これは文字コードの判定を確認するための十分に長い段落です。"));
+
+    let options = ExtractionOptions {
+        strip_styles: false,
+        ..Default::default()
+    };
+    let article = parser
+        .parse_offline_bytes_with_options(
+            vec![bytes.into_owned()],
+            None,
+            Some(&config),
+            None,
+            &options,
+        )
+        .unwrap();
+    assert_eq!(article.encoding.as_deref(), Some("Shift_JIS"));
+    assert!(article.html.unwrap().contains(r#"style="color: red""#));
+
+    let article = parser
+        .parse_offline(vec![html.into()], Some(&config), None)
+        .unwrap();
+    assert_eq!(article.encoding, None);
+}
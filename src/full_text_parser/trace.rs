@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+use url::Url;
+
+/// A decision made while extracting an article, see `collect_trace`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// A `SiteExtractor` extracted the article, the site config wasn't used
+    SiteExtractor { name: String },
+    /// Site config found for the host of the article
+    SiteConfig { file: String },
+    /// No site config matched the host, only `global.txt` is used
+    NoSiteConfig { host: String },
+    /// A `strip`, `strip_id_or_class`, `strip_image_src` or `strip_attr` rule of the
+    /// site config or `global.txt` removed `nodes` elements or attributes
+    Strip {
+        directive: &'static str,
+        rule: String,
+        nodes: usize,
+    },
+    /// A `wrap_in`, `dissolve` or `move_into` rule of the site config or `global.txt`
+    /// changed `nodes` elements
+    Restructure {
+        directive: &'static str,
+        rule: String,
+        nodes: usize,
+    },
+    /// A `body` rule was evaluated and matched `nodes` elements
+    Body { rule: String, nodes: usize },
+    /// No `body` rule matched and the page was handed to Readability
+    ReadabilityFallback,
+    /// Readability found `text_length` characters with these flags. The content is
+    /// `accepted` if it reaches `ExtractionOptions::char_threshold`.
+    ReadabilityAttempt {
+        strip_unlikely: bool,
+        weigh_classes: bool,
+        clean_conditionally: bool,
+        text_length: usize,
+        accepted: bool,
+    },
+    /// No attempt of Readability was accepted, the longest one is used
+    ReadabilityLongestAttempt { text_length: usize },
+    /// A `single_page_link` or `next_page_link` rule yielded a link
+    PageLink { rule: String, url: Url },
+}
+
+thread_local! {
+    static TRACE: RefCell<Option<Vec<TraceEvent>>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, e.g. a call of `FullTextParser::parse_with_provider`, and return its result
+/// next to the events recorded on this thread meanwhile. Nothing is recorded outside of
+/// `collect_trace`.
+///
+/// The trace is kept per thread: work `f` hands to other threads, or a future polled
+/// after `f` returned, isn't part of it. Any synchronous entry point can be traced this
+/// way, e.g. `collect_trace(|| parser.parse_offline(pages, None, url))`.
+pub fn collect_trace<T>(f: impl FnOnce() -> T) -> (T, Vec<TraceEvent>) {
+    /// Restores the outer trace even if `f` panics
+    struct Guard(Option<Vec<TraceEvent>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            TRACE.with_borrow_mut(|trace| *trace = outer);
+        }
+    }
+
+    let guard = Guard(TRACE.replace(Some(Vec::new())));
+    let result = f();
+    let events = TRACE.replace(None).unwrap_or_default();
+    drop(guard);
+    (result, events)
+}
+
+/// Record the event returned by `event` if a trace is being collected
+pub(crate) fn record(event: impl FnOnce() -> TraceEvent) {
+    TRACE.with_borrow_mut(|trace| {
+        if let Some(trace) = trace {
+            trace.push(event());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_trace, record, TraceEvent};
+
+    #[test]
+    fn nested_traces() {
+        record(|| unreachable!("not tracing"));
+
+        let ((_, inner), outer) = collect_trace(|| {
+            record(|| TraceEvent::ReadabilityFallback);
+            let inner = collect_trace(|| {
+                record(|| TraceEvent::ReadabilityLongestAttempt { text_length: 1 })
+            });
+            record(|| TraceEvent::ReadabilityFallback);
+            inner
+        });
+
+        assert_eq!(
+            inner,
+            vec![TraceEvent::ReadabilityLongestAttempt { text_length: 1 }]
+        );
+        assert_eq!(outer, vec![TraceEvent::ReadabilityFallback; 2]);
+    }
+}
@@ -21,15 +21,27 @@
 mod article;
 pub mod clean;
 mod constants;
-mod error;
 mod full_text_parser;
 mod image_object;
 mod util;
 mod video_object;
 
-pub use article::Article;
+pub use article::{Article, Author, MetadataSource};
 #[doc(hidden)]
 pub use full_text_parser::config::ConfigEntry as FtrConfigEntry;
+pub use full_text_parser::config::Header;
+pub use full_text_parser::config::{ConfigDiagnostic, ConfigError};
+#[doc(hidden)]
+pub use full_text_parser::config::{MoveInto, PageLink, WrapIn};
+pub use full_text_parser::decode_html;
+pub use full_text_parser::error::FullTextParserError;
+pub use full_text_parser::ExtractionOptions;
 #[doc(hidden)]
 pub use full_text_parser::FullTextParser;
+pub use full_text_parser::PageProvider;
 pub use full_text_parser::Readability;
+pub use full_text_parser::{collect_trace, TraceEvent};
+pub use full_text_parser::{snapshot_file_name, SiteTestReport, SiteTestResult, TestUrlResult};
+pub use full_text_parser::{Cookie, RequestProfile};
+pub use full_text_parser::{FnPass, Pass, PassContext, Passes};
+pub use full_text_parser::{NextData, NuxtState, SiteExtractor};
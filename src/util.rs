@@ -2,12 +2,13 @@ use libxml::{
     tree::{Document, Node, NodeType},
     xpath::Context,
 };
+use regex::Regex;
 use std::fmt::Write;
 use std::{collections::HashSet, fs::DirEntry};
 
 use crate::{
     constants::{self, NEGATIVE_LEAD_IMAGE_URL_HINTS_REGEX},
-    full_text_parser::error::FullTextParserError,
+    full_text_parser::{error::FullTextParserError, ExtractionOptions},
     image_object::ImageObject,
     video_object::VideoObject,
 };
@@ -103,9 +104,16 @@ impl Util {
         Ok(val.trim().to_string())
     }
 
-    pub fn strip_node(context: &Context, xpath: &str) -> Result<(), FullTextParserError> {
+    // Remove all nodes matching the xpath and return how many were removed.
+    // Embeds are kept if they match `allowed_video` or `strip_embeds` is disabled.
+    pub fn strip_node(
+        context: &Context,
+        xpath: &str,
+        options: &ExtractionOptions,
+    ) -> Result<usize, FullTextParserError> {
         let node_vec = Util::evaluate_xpath(context, xpath, false)?;
         let node_vec_clone = node_vec.clone();
+        let mut removed = 0;
 
         for mut node in node_vec {
             if node.is_null() {
@@ -114,10 +122,11 @@ impl Util {
 
             let tag_name = node.get_name();
             if constants::EMBED_TAG_NAMES.contains(tag_name.to_uppercase().as_str())
-                && node
-                    .get_attributes()
-                    .iter()
-                    .any(|(_name, value)| constants::VIDEOS.is_match(value))
+                && (!options.strip_embeds
+                    || node
+                        .get_attributes()
+                        .iter()
+                        .any(|(_name, value)| options.allowed_video.is_match(value)))
             {
                 continue;
             }
@@ -127,8 +136,9 @@ impl Util {
             }
 
             node.unlink();
+            removed += 1;
         }
-        Ok(())
+        Ok(removed)
     }
 
     fn parent_part_of_result(node: &Node, xpath_result: &[Node]) -> bool {
@@ -148,7 +158,7 @@ impl Util {
     pub fn strip_id_or_class(
         context: &Context,
         id_or_class: &str,
-    ) -> Result<(), FullTextParserError> {
+    ) -> Result<usize, FullTextParserError> {
         let xpath = &format!(
             "//*[contains(@class, '{}') or contains(@id, '{}')]",
             id_or_class, id_or_class
@@ -161,14 +171,144 @@ impl Util {
 
         let query = &format!("{}[not(ancestor::{})]", xpath, ancestor);
         let node_vec = Util::evaluate_xpath(context, query, false)?;
+        let mut removed = 0;
         for mut node in node_vec {
             if node.is_null() {
                 continue;
             }
 
             node.unlink();
+            removed += 1;
         }
-        Ok(())
+        Ok(removed)
+    }
+
+    // Remove all attributes matching the xpath, e.g. `//img/@width`.
+    pub fn strip_attribute(context: &Context, xpath: &str) -> Result<usize, FullTextParserError> {
+        let node_vec = Util::evaluate_xpath(context, xpath, false)?;
+        let mut removed = 0;
+        for node in node_vec {
+            if node.get_type() != Some(NodeType::AttributeNode) {
+                log::debug!("xpath '{xpath}' matched a node that is not an attribute");
+                continue;
+            }
+
+            if let Some(mut parent) = node.get_parent() {
+                let name = node.get_name();
+                match parent.remove_attribute(&name) {
+                    Ok(()) => removed += 1,
+                    Err(error) => {
+                        log::warn!("Failed to remove attribute '{name}' from node: {error}")
+                    }
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    // Wrap every node matching the xpath in a new element with the given tag name
+    // and return how many were wrapped.
+    pub fn wrap_in(
+        context: &Context,
+        document: &Document,
+        tag: &str,
+        xpath: &str,
+    ) -> Result<usize, FullTextParserError> {
+        let node_vec = Util::evaluate_xpath(context, xpath, false)?;
+        let mut wrapped = 0;
+        for node in node_vec {
+            if node.is_null() {
+                continue;
+            }
+
+            let Some(mut parent) = node.get_parent() else {
+                continue;
+            };
+
+            let mut wrapper =
+                Node::new(tag, None, document).map_err(|()| FullTextParserError::Xml)?;
+            let mut node = parent
+                .replace_child_node(wrapper.clone(), node)
+                .map_err(|error| {
+                    log::error!("{error}");
+                    FullTextParserError::Xml
+                })?;
+            wrapper.add_child(&mut node).map_err(|error| {
+                log::error!("{error}");
+                FullTextParserError::Xml
+            })?;
+            wrapped += 1;
+        }
+        Ok(wrapped)
+    }
+
+    // Replace every node matching the xpath with its children and return how many were
+    // dissolved.
+    pub fn dissolve(context: &Context, xpath: &str) -> Result<usize, FullTextParserError> {
+        let node_vec = Util::evaluate_xpath(context, xpath, false)?;
+        let mut dissolved = 0;
+        for mut node in node_vec {
+            if node.is_null() || node.get_parent().is_none() {
+                continue;
+            }
+
+            for mut child in node.get_child_nodes() {
+                child.unlink();
+                node.add_prev_sibling(&mut child).map_err(|error| {
+                    log::error!("{error}");
+                    FullTextParserError::Xml
+                })?;
+            }
+
+            node.unlink();
+            dissolved += 1;
+        }
+        Ok(dissolved)
+    }
+
+    // Move every node matching the xpath into the first node matching the target xpath
+    // and return how many were moved.
+    // The target is evaluated relative to the root element, so `body` works as well.
+    pub fn move_into(
+        context: &Context,
+        document: &Document,
+        target: &str,
+        xpath: &str,
+    ) -> Result<usize, FullTextParserError> {
+        let root = document
+            .get_root_element()
+            .ok_or(FullTextParserError::Xml)?;
+        let mut target_node = context
+            .node_evaluate(target, &root)
+            .map_err(|()| {
+                log::debug!("Evaluation of xpath '{}' yielded no results", target);
+                FullTextParserError::Xml
+            })?
+            .get_nodes_as_vec()
+            .into_iter()
+            .next()
+            .ok_or(FullTextParserError::Xml)?;
+
+        let node_vec = Util::evaluate_xpath(context, xpath, false)?;
+        let mut moved = 0;
+        for mut node in node_vec {
+            if node.is_null() || node == target_node {
+                continue;
+            }
+
+            // moving a node into its own descendant would detach it from the document
+            if Util::get_node_ancestors(&target_node, None).contains(&node) {
+                continue;
+            }
+
+            node.unlink();
+            target_node.add_child(&mut node).map_err(|error| {
+                log::error!("{error}");
+                FullTextParserError::Xml
+            })?;
+            moved += 1;
+        }
+        Ok(moved)
     }
 
     pub fn get_signature(node: &Node) -> String {
@@ -490,9 +630,11 @@ impl Util {
     }
 
     // Determine whether element has any children block level elements.
+    // Video embeds kept by the allowlist count as blocks so their wrappers stay <div>s.
     pub fn has_child_block_element(node: &Node) -> bool {
         node.get_child_nodes().iter().any(|node| {
             constants::DIV_TO_P_ELEMS.contains(node.get_name().to_uppercase().as_str())
+                || node.has_attribute(constants::ALLOWED_VIDEO_ATTR)
                 || Self::has_child_block_element(node)
         })
     }
@@ -596,7 +738,7 @@ impl Util {
 
     // Clean an element of all tags of type "tag" if they look fishy.
     // "Fishy" is an algorithm based on content length, classnames, link density, number of images & embeds, etc.
-    pub fn clean_conditionally(root: &mut Node, tag: &str) {
+    pub fn clean_conditionally(root: &mut Node, tag: &str, allowed_video: &Regex) {
         // Gather counts for other typical elements embedded within.
         // Traverse backwards so we can remove nodes at the same time
         // without effecting the traversal.
@@ -609,13 +751,13 @@ impl Util {
                 continue;
             }
 
-            if Self::should_remove(&node, tag) {
+            if Self::should_remove(&node, tag, allowed_video) {
                 node.unlink();
             }
         }
     }
 
-    fn should_remove(node: &Node, tag: &str) -> bool {
+    fn should_remove(node: &Node, tag: &str, allowed_video: &Regex) -> bool {
         // First check if this node IS data table, in which case don't remove it.
         let mut is_list = tag == "ul" || tag == "ol";
         if !is_list {
@@ -665,9 +807,14 @@ impl Util {
 
             for embed_tag in embed_tags {
                 for embed_node in Self::get_elements_by_tag_name(node, embed_tag) {
+                    // Embeds kept by the video allowlist are marked in `prep_content`.
+                    if embed_node.has_attribute(constants::ALLOWED_VIDEO_ATTR) {
+                        return false;
+                    }
+
                     // If this embed has attribute that matches video regex, don't delete it.
                     for (_name, value) in embed_node.get_attributes() {
-                        if constants::VIDEOS.is_match(&value) {
+                        if allowed_video.is_match(&value) {
                             return false;
                         }
                     }
@@ -1154,8 +1301,6 @@ mod tests {
     use crate::FullTextParser;
 
     fn replace_brs(source: &str, expected: &str) {
-        libxml::tree::node::set_node_rc_guard(10);
-
         let document = FullTextParser::parse_html_string_patched(source).unwrap();
         let root = document.get_root_element().unwrap();
         let body = root.get_first_child().unwrap();
@@ -1195,8 +1340,6 @@ mod tests {
     }
 
     fn replace_emojis(source: &str, expected: &str) {
-        libxml::tree::node::set_node_rc_guard(10);
-
         let document = FullTextParser::parse_html_string_patched(source).unwrap();
         let root = document.get_root_element().unwrap();
         let body = root.get_first_child().unwrap();